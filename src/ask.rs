@@ -1,5 +1,17 @@
-use std::io::{Cursor, Write, BufRead, Seek, SeekFrom};
+use std::io::Write;
+#[cfg(test)]
+use std::io::Cursor;
 use crate::marksman::*;
+use crate::eating::*;
+use crate::riders::*;
+use crate::strings::*;
+use crate::prompt::*;
+
+pub const ASK_EAT: &str = "Do you want to eat 1) Poorly, 2) Moderately or 3) Well? ";
+pub const ASK_TACTICS: &str = "Tactics: 1) Run, 2) Attack, 3) Continue or 4) Circle wagons? ";
+pub const ASK_CONTINUE: &str = "Do you want to 1) Continue? ";
+pub const ASK_HUNT_CONTINUE: &str = "Do you want to 1) Hunt, 2) Continue or 3) Save and quit? ";
+pub const ASK_FORT_HUNT_CONTINUE: &str = "Do you want to 1) Stop at a Fort, 2) Hunt, 3) Continue or 4) Save and quit? ";
 
 #[derive(PartialEq)]
 #[derive(Debug)]
@@ -7,254 +19,132 @@ pub enum TurnAction {
     Fort,
     Hunt,
     Continue,
+    /// Stop playing for now, saving the game to be resumed later
+    SaveAndQuit,
 }
 
-#[macro_export]
-macro_rules! ask {
-    ( $question: expr, $out: expr, $input: expr ) => {
-        {
-            $out.write($question.as_bytes()).unwrap();
-            $out.flush().unwrap();
-
-            let mut buffer = String::new();
-            $input.read_line(&mut buffer).unwrap();
-            buffer.retain(|buffer| !buffer.is_whitespace());
-            match buffer.parse::<u32>() {
-                Ok(n) => n,
-                Err(_e) => 0
-            }
-        }
-    };
-}
-
-/// Ask the user to answer a Yes/No question (default to Yes)
-#[macro_export]
-macro_rules! ask_yn {
-    ( $question: expr, $out: expr, $input: expr ) => {
-        {
-            $out.write($question.as_bytes()).unwrap();
-            $out.flush().unwrap();
-
-            let mut buffer = String::new();
-            $input.read_line(&mut buffer).unwrap();
-            buffer.retain(|buffer| !buffer.is_whitespace());
-
-            buffer.push('y');
-            match buffer.chars().next().unwrap() {
-                'n' => false,
-                _ => true,
-            }
-        }
-    };
-}
-
-#[macro_export]
-macro_rules! ask_ok {
-    ( $ask: expr ) => {
-        {
-            loop {
-                match $ask {
-                    Ok(_) => { break; }
-                    Err(e) => println!("{}", e),
-                }
-            }
-        }
-    };
+pub fn ask_marksman<W: Write, P: Prompter>(out: &mut W, prompter: &mut P) -> Result<MarksmanQuality, PromptError> {
+    Ok(MarksmanQuality::from_u32(prompter.menu(ASK_MARKSMAN, 5, out)?))
 }
 
-pub fn ask_marksman<W: Write, R: BufRead>(out: &mut W, input: &mut R) -> MarksmanQuality {
-    loop {
-        let marksman = MarksmanQuality::from_u32(ask!(include_str!("../strings/ask_marksman.txt"), out, input));
-        if marksman != MarksmanQuality::Unknown { return marksman; }
+pub fn ask_eat<W: Write, P: Prompter>(out: &mut W, prompter: &mut P) -> Result<EatingQuality, PromptError> {
+    match prompter.menu(ASK_EAT, 3, out)? {
+        1 => Ok(EatingQuality::Poorly),
+        2 => Ok(EatingQuality::Moderately),
+        _ => Ok(EatingQuality::Well),
     }
 }
 
-pub fn ask_continue<W: Write, R: BufRead>(out: &mut W, input: &mut R) -> TurnAction {
-    loop {
-        let action = ask!("Do you want to 1) Continue? ", out, input);
-        if action == 1 { return TurnAction::Continue; }
-    }
+pub fn ask_tactics<W: Write, P: Prompter>(out: &mut W, prompter: &mut P) -> Result<RiderTactic, PromptError> {
+    Ok(RiderTactic::from_u32(prompter.menu(ASK_TACTICS, 4, out)?))
 }
 
-pub fn ask_hunt_continue<W: Write, R: BufRead>(out: &mut W, input: &mut R) -> TurnAction {
-    loop {
-        let action = ask!("Do you want to 1) Hunt or 2) Continue? ", out, input);
-        match action {
-            1 => return TurnAction::Hunt,
-            2 => return TurnAction::Continue,
-            _ => continue,
-        }
-    }
+pub fn ask_continue<W: Write, P: Prompter>(out: &mut W, prompter: &mut P) -> Result<TurnAction, PromptError> {
+    prompter.menu(ASK_CONTINUE, 1, out)?;
+    Ok(TurnAction::Continue)
 }
 
-pub fn ask_fort_hunt_continue<W: Write, R: BufRead>(out: &mut W, input: &mut R) -> TurnAction {
-    loop {
-        let action = ask!("Do you want to 1) Stop at a Fort, 2) Hunt or 3) Continue? ", out, input);
-        match action {
-            1 => return TurnAction::Fort,
-            2 => return TurnAction::Hunt,
-            3 => return TurnAction::Continue,
-            _ => continue,
-        }
+pub fn ask_hunt_continue<W: Write, P: Prompter>(out: &mut W, prompter: &mut P) -> Result<TurnAction, PromptError> {
+    match prompter.menu(ASK_HUNT_CONTINUE, 3, out)? {
+        1 => Ok(TurnAction::Hunt),
+        2 => Ok(TurnAction::Continue),
+        _ => Ok(TurnAction::SaveAndQuit),
     }
 }
 
-#[test]
-fn test_ask_value_1() {
-    let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"1").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    // Assert
-    let value = ask!("test? ", cout, cin);
-    assert_eq!(1, value);
-}
-
-#[test]
-fn test_ask_value_200() {
-    let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"200").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    // Assert
-    let value = ask!("test? ", cout, cin);
-    assert_eq!(200, value);
+pub fn ask_fort_hunt_continue<W: Write, P: Prompter>(out: &mut W, prompter: &mut P) -> Result<TurnAction, PromptError> {
+    match prompter.menu(ASK_FORT_HUNT_CONTINUE, 4, out)? {
+        1 => Ok(TurnAction::Fort),
+        2 => Ok(TurnAction::Hunt),
+        3 => Ok(TurnAction::Continue),
+        _ => Ok(TurnAction::SaveAndQuit),
+    }
 }
 
-
 #[test]
-fn test_ask_value_999_with_return() {
+fn test_ask_marksman() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"999\r\n").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    // Assert
-    let value = ask!("test? ", cout, cin);
-    assert_eq!(999, value);
+    let mut prompter = ScriptedPrompter::new(&["1"]);
+    let action = ask_marksman(&mut cout, &mut prompter);
+    assert_eq!(Ok(MarksmanQuality::Ace), action);
 }
 
 #[test]
-fn test_ask_yn_value_y() {
+fn test_ask_continue() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"y").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    // Assert
-    let value = ask_yn!("test? ", cout, cin);
-    assert_eq!(true, value);
+    let mut prompter = ScriptedPrompter::new(&["1"]);
+    let action = ask_continue(&mut cout, &mut prompter);
+    assert_eq!(Ok(TurnAction::Continue), action);
 }
 
 #[test]
-fn test_ask_yn_value_n() {
+fn test_ask_hunt_continue_hunt() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"n").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    // Assert
-    let value = ask_yn!("test? ", cout, cin);
-    assert_eq!(false, value);
+    let mut prompter = ScriptedPrompter::new(&["1"]);
+    let action = ask_hunt_continue(&mut cout, &mut prompter);
+    assert_eq!(Ok(TurnAction::Hunt), action);
 }
 
 #[test]
-fn test_ask_yn_value_test() {
+fn test_ask_hunt_continue_continue() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"test").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    // Assert
-    let value = ask_yn!("test? ", cout, cin);
-    assert_eq!(true, value);
+    let mut prompter = ScriptedPrompter::new(&["2"]);
+    let action = ask_hunt_continue(&mut cout, &mut prompter);
+    assert_eq!(Ok(TurnAction::Continue), action);
 }
 
 #[test]
-fn test_ask_yn_value_invalid() {
+fn test_ask_fort_hunt_continue_fort() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b" ").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    // Assert
-    let value = ask_yn!("test? ", cout, cin);
-    assert_eq!(true, value);
-}
-
-#[test]
-fn test_ask_ok_success() {
-    let mut okay_result: bool = false;
-    let mut func = || -> Result<(), core::fmt::Error> {
-        okay_result = true;
-        Ok(())
-    };
-    // Assert
-    ask_ok!(func());
-    assert!(okay_result);
+    let mut prompter = ScriptedPrompter::new(&["1"]);
+    let action = ask_fort_hunt_continue(&mut cout, &mut prompter);
+    assert_eq!(Ok(TurnAction::Fort), action);
 }
 
 #[test]
-fn test_ask_marksman() {
+fn test_ask_fort_hunt_continue_hunt() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"1").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    let action = ask_marksman(&mut cout, &mut cin);
-    assert_eq!(MarksmanQuality::Ace, action);
+    let mut prompter = ScriptedPrompter::new(&["2"]);
+    let action = ask_fort_hunt_continue(&mut cout, &mut prompter);
+    assert_eq!(Ok(TurnAction::Hunt), action);
 }
 
 #[test]
-fn test_ask_continue() {
+fn test_ask_fort_hunt_continue_continue() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"1").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    let action = ask_continue(&mut cout, &mut cin);
-    assert_eq!(TurnAction::Continue, action);
+    let mut prompter = ScriptedPrompter::new(&["3"]);
+    let action = ask_fort_hunt_continue(&mut cout, &mut prompter);
+    assert_eq!(Ok(TurnAction::Continue), action);
 }
 
 #[test]
-fn test_ask_hunt_continue_hunt() {
+fn test_ask_hunt_continue_save() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"1").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    let action = ask_hunt_continue(&mut cout, &mut cin);
-    assert_eq!(TurnAction::Hunt, action);
+    let mut prompter = ScriptedPrompter::new(&["3"]);
+    let action = ask_hunt_continue(&mut cout, &mut prompter);
+    assert_eq!(Ok(TurnAction::SaveAndQuit), action);
 }
 
 #[test]
-fn test_ask_hunt_continue_continue() {
+fn test_ask_fort_hunt_continue_save() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"2").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    let action = ask_hunt_continue(&mut cout, &mut cin);
-    assert_eq!(TurnAction::Continue, action);
+    let mut prompter = ScriptedPrompter::new(&["4"]);
+    let action = ask_fort_hunt_continue(&mut cout, &mut prompter);
+    assert_eq!(Ok(TurnAction::SaveAndQuit), action);
 }
 
 #[test]
-fn test_ask_fort_hunt_continue_fort() {
+fn test_ask_eat() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"1").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    let action = ask_fort_hunt_continue(&mut cout, &mut cin);
-    assert_eq!(TurnAction::Fort, action);
+    let mut prompter = ScriptedPrompter::new(&["0", "4", "2"]);
+    let eating = ask_eat(&mut cout, &mut prompter);
+    assert_eq!(Ok(EatingQuality::Moderately), eating);
 }
 
 #[test]
-fn test_ask_fort_hunt_continue_hunt() {
+fn test_ask_tactics() {
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"2").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    let action = ask_fort_hunt_continue(&mut cout, &mut cin);
-    assert_eq!(TurnAction::Hunt, action);
+    let mut prompter = ScriptedPrompter::new(&["5", "4"]);
+    let tactic = ask_tactics(&mut cout, &mut prompter);
+    assert_eq!(Ok(RiderTactic::CircleWagons), tactic);
 }
-
-#[test]
-fn test_ask_fort_hunt_continue_continue() {
-    let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"3").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
-    let action = ask_fort_hunt_continue(&mut cout, &mut cin);
-    assert_eq!(TurnAction::Continue, action);
-}
\ No newline at end of file
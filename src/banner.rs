@@ -1,15 +1,18 @@
+use std::io::Write;
+use crate::strings::*;
+#[cfg(test)]
 use std::str;
-use std::io::{Cursor, Write, Read, Seek, SeekFrom};
+#[cfg(test)]
+use std::io::{Cursor, Read, Seek, SeekFrom};
 
 pub fn print_banner<W: Write>(out: &mut W) {
-    let banner = include_str!("../strings/banner.txt");
-    out.write(banner.as_bytes()).unwrap();
+    out.write_all(BANNER.as_bytes()).unwrap();
     out.flush().unwrap();
 }
 
 #[test]
 fn test_banner() {
-    let banner = include_str!("../strings/banner.txt");
+    let banner = BANNER;
     let mut c = Cursor::new(Vec::new());
 
     print_banner(&mut c);
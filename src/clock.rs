@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// A source of time for timed prompts (such as shooting)
+pub trait Clock {
+    /// Retrieve the time elapsed since the clock was created
+    fn now(&mut self) -> Duration;
+}
+
+/// The real (monotonic) clock used when playing the game
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&mut self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that follows a script, so timed prompts can be tested deterministically
+///     Each reading of the clock advances it by the next scripted tick,
+///     once the script runs out time stands still
+pub struct ScriptedClock {
+    ticks: VecDeque<Duration>,
+    current: Duration,
+}
+
+impl ScriptedClock {
+    pub fn new(ticks: &[Duration]) -> ScriptedClock {
+        ScriptedClock {
+            ticks: ticks.iter().copied().collect(),
+            current: Duration::ZERO,
+        }
+    }
+
+    /// Script a series of reaction times, one per timed prompt
+    ///     A timed prompt reads the clock when it asks and again when it is answered
+    pub fn reactions(reactions: &[Duration]) -> ScriptedClock {
+        let ticks: Vec<Duration> = reactions.iter().flat_map(|reaction| vec![Duration::ZERO, *reaction]).collect();
+        ScriptedClock::new(&ticks)
+    }
+}
+
+impl Clock for ScriptedClock {
+    fn now(&mut self) -> Duration {
+        self.current += self.ticks.pop_front().unwrap_or(Duration::ZERO);
+        self.current
+    }
+}
+
+/// Time how long it takes for a prompt to be answered
+pub fn timed<C: Clock, F: FnOnce()>(clock: &mut C, read: F) -> Duration {
+    let start = clock.now();
+    read();
+    clock.now() - start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_clock() {
+        let mut clock = SystemClock::new();
+        let first = clock.now();
+        assert!(clock.now() >= first);
+    }
+
+    #[test]
+    fn test_scripted_clock() {
+        let mut clock = ScriptedClock::new(&[Duration::from_secs(1), Duration::from_secs(2)]);
+        assert_eq!(Duration::from_secs(1), clock.now());
+        assert_eq!(Duration::from_secs(3), clock.now());
+        assert_eq!(Duration::from_secs(3), clock.now());
+    }
+
+    #[test]
+    fn test_scripted_clock_reactions() {
+        let mut clock = ScriptedClock::reactions(&[Duration::from_millis(1400), Duration::from_secs(5)]);
+        assert_eq!(Duration::from_millis(1400), timed(&mut clock, || {}));
+        assert_eq!(Duration::from_secs(5), timed(&mut clock, || {}));
+        assert_eq!(Duration::ZERO, timed(&mut clock, || {}));
+    }
+}
@@ -0,0 +1,88 @@
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum EatingQuality {
+    Poorly,
+    Moderately,
+    Well,
+}
+
+impl EatingQuality {
+    /// Retrieve the eating quality for a menu choice, 1 (Poorly) through 3 (Well)
+    pub fn from_u32(value: u32) -> Option<EatingQuality> {
+        match value {
+            1 => Some(EatingQuality::Poorly),
+            2 => Some(EatingQuality::Moderately),
+            3 => Some(EatingQuality::Well),
+            _ => None,
+        }
+    }
+
+    /// Retrieve how well the party is eating (E in the BASIC code), 1 (Poorly) through 3 (Well)
+    pub fn value(&self) -> u32 {
+        match self {
+            EatingQuality::Poorly => 1,
+            EatingQuality::Moderately => 2,
+            EatingQuality::Well => 3,
+        }
+    }
+
+    /// Retrieve the food eaten in a turn, calculated as (BASIC code):
+    ///     8+5*E, where E is 1 (Poorly) through 3 (Well)
+    pub fn food_eaten(&self) -> u32 {
+        match self {
+            EatingQuality::Poorly => 13,
+            EatingQuality::Moderately => 18,
+            EatingQuality::Well => 23,
+        }
+    }
+
+    /// Retrieve the chance (0.0 - 1.0) that an illness takes hold of the party
+    ///     Eating poorly always leaves you open to illness, eating well halves the chance
+    pub fn illness_chance(&self) -> f64 {
+        match self {
+            EatingQuality::Poorly => 1.0,
+            EatingQuality::Moderately => 0.75,
+            EatingQuality::Well => 0.5,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u32_poorly() {
+        assert_eq!(Some(EatingQuality::Poorly), EatingQuality::from_u32(1));
+    }
+
+    #[test]
+    fn u32_moderately() {
+        assert_eq!(Some(EatingQuality::Moderately), EatingQuality::from_u32(2));
+    }
+
+    #[test]
+    fn u32_well() {
+        assert_eq!(Some(EatingQuality::Well), EatingQuality::from_u32(3));
+    }
+
+    #[test]
+    fn u32_unknown() {
+        assert_eq!(None, EatingQuality::from_u32(0));
+        assert_eq!(None, EatingQuality::from_u32(4));
+    }
+
+    #[test]
+    fn food_eaten() {
+        assert_eq!(13, EatingQuality::Poorly.food_eaten());
+        assert_eq!(18, EatingQuality::Moderately.food_eaten());
+        assert_eq!(23, EatingQuality::Well.food_eaten());
+    }
+
+    #[test]
+    fn illness_chance() {
+        assert!(EatingQuality::Poorly.illness_chance() > EatingQuality::Moderately.illness_chance());
+        assert!(EatingQuality::Moderately.illness_chance() > EatingQuality::Well.illness_chance());
+    }
+}
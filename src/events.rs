@@ -0,0 +1,468 @@
+use std::io::Write;
+use rand::Rng;
+use chrono::NaiveDate;
+use crate::clock::*;
+use crate::eating::*;
+use crate::finish::*;
+use crate::hunt::*;
+use crate::illness::*;
+use crate::marksman::*;
+use crate::prompt::*;
+use crate::shooting::*;
+use crate::supplies::*;
+use crate::trip::*;
+use crate::weather::*;
+#[cfg(test)]
+use std::io::Cursor;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand::rngs::StdRng;
+#[cfg(test)]
+use std::time::Duration;
+#[cfg(test)]
+use crate::money::*;
+#[cfg(test)]
+use crate::testing::*;
+
+const EVENT_WAGON_BREAKS_DOWN: &str = "Wagon breaks down--lose time and supplies fixing it\n";
+const EVENT_OX_INJURES_LEG: &str = "Ox injures leg---slows you down rest of trip\n";
+const EVENT_DAUGHTER_BREAKS_ARM: &str = "Bad luck---your daughter broke her arm\nYou had to stop and use supplies to make a sling\n";
+const EVENT_OX_WANDERS_OFF: &str = "Ox wanders off---spend time looking for it\n";
+const EVENT_SON_GETS_LOST: &str = "Your son gets lost---spend half the day looking for him\n";
+const EVENT_UNSAFE_WATER: &str = "Unsafe water--lose time looking for clean spring\n";
+const EVENT_HEAVY_RAINS: &str = "Heavy rains---time and supplies lost\n";
+const EVENT_BANDITS: &str = "Bandits attack\n";
+const EVENT_BANDITS_CASH: &str = "You ran out of bullets---they get lots of cash\n";
+const EVENT_BANDITS_SHOT: &str = "You got shot in the leg and they took one of your oxen\nBetter have a doc look at your wound\n";
+const EVENT_BANDITS_QUICK_DRAW: &str = "Quickest draw outside of Dodge City!!!\nYou got 'em!\n";
+const EVENT_WAGON_FIRE: &str = "There was a fire in your wagon--food and supplies damage!\n";
+const EVENT_HEAVY_FOG: &str = "Lose your way in heavy fog---time is lost\n";
+const EVENT_POISONOUS_SNAKE: &str = "You killed a poisonous snake after it bit you\n";
+const EVENT_WAGON_SWAMPED: &str = "Wagon gets swamped fording river--lose food and clothes\n";
+const EVENT_WILD_ANIMALS: &str = "Wild animals attack!\n";
+const EVENT_WILD_ANIMALS_OVERPOWERED: &str = "You were too low on bullets--the wolves overpowered you\n";
+const EVENT_WILD_ANIMALS_NICE: &str = "Nice shootin' pardner---they didn't get much\n";
+const EVENT_WILD_ANIMALS_SLOW: &str = "Slow on the draw---they got at your food and clothes\n";
+const EVENT_COLD_WEATHER_WARM: &str = "Cold weather---brrrrrrr!---you have enough clothing to keep you warm\n";
+const EVENT_COLD_WEATHER_COLD: &str = "Cold weather---brrrrrrr!---you don't have enough clothing to keep you warm\n";
+const EVENT_HAIL_STORM: &str = "Hail storm---supplies damaged\n";
+const EVENT_NO_REPAIRS: &str = "You had nothing left to make repairs with---it took days to patch up the wagon\n";
+const EVENT_HELPFUL_INDIANS: &str = "Helpful Indians show you where to find more food\n";
+
+/// Time lost patching up the wagon without any supplies to repair it
+pub const NO_REPAIRS_MILES: u32 = 40;
+
+/// Repair the wagon using miscellaneous supplies
+///     Without enough supplies the repairs take much longer, losing more time
+pub fn repair_wagon<W: Write>(repairs: u32, supplies: &mut Supplies, trip: &mut Trip, out: &mut W) {
+    if !supplies.use_repairs(repairs) {
+        out.write_all(EVENT_NO_REPAIRS.as_bytes()).unwrap();
+        trip.reverse(NO_REPAIRS_MILES);
+    }
+}
+
+/// The events that can happen along each leg of the trip
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Event {
+    WagonBreaksDown,
+    OxInjuresLeg,
+    DaughterBreaksArm,
+    OxWandersOff,
+    SonGetsLost,
+    UnsafeWater,
+    HeavyRains,
+    Bandits,
+    WagonFire,
+    HeavyFog,
+    PoisonousSnake,
+    WagonSwamped,
+    WildAnimals,
+    ColdWeather,
+    HailStorm,
+    Illness,
+    HelpfulIndians,
+}
+
+/// The table of events and their weights (the chance out of 100 of each happening)
+pub const EVENTS: [(Event, u32); 17] = [
+    (Event::WagonBreaksDown, 6),
+    (Event::OxInjuresLeg, 5),
+    (Event::DaughterBreaksArm, 2),
+    (Event::OxWandersOff, 2),
+    (Event::SonGetsLost, 2),
+    (Event::UnsafeWater, 5),
+    (Event::HeavyRains, 10),
+    (Event::Bandits, 3),
+    (Event::WagonFire, 2),
+    (Event::HeavyFog, 5),
+    (Event::PoisonousSnake, 2),
+    (Event::WagonSwamped, 10),
+    (Event::WildAnimals, 10),
+    (Event::ColdWeather, 5),
+    (Event::HailStorm, 10),
+    (Event::Illness, 16),
+    (Event::HelpfulIndians, 5),
+];
+
+impl Event {
+    /// Roll exactly one event from the weighted table of events
+    ///     Once the weather turns cold (clothing is required) heavy rains become cold weather, before then cold weather is heavy rains
+    pub fn roll<G: Rng>(date: NaiveDate, miles: u32, rng: &mut G) -> Event {
+        let total: u32 = EVENTS.iter().map(|(_, weight)| weight).sum();
+        let mut roll = rng.gen_range(0..total);
+        let mut event = Event::HelpfulIndians;
+        for (candidate, weight) in EVENTS.iter() {
+            if roll < *weight {
+                event = *candidate;
+                break;
+            }
+            roll -= weight;
+        }
+
+        let cold = clothing_required(date, miles) > 0;
+        match event {
+            Event::HeavyRains if cold => Event::ColdWeather,
+            Event::ColdWeather if !cold => Event::HeavyRains,
+            _ => event,
+        }
+    }
+
+    /// Apply the effects of the event to the party
+    ///     Some events are fatal, returning the cause of death
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply<W: Write, P: Prompter, G: Rng, C: Clock>(&self, supplies: &mut Supplies, trip: &mut Trip, health: &mut Health,
+            marksman: &MarksmanQuality, eating: &EatingQuality, out: &mut W, prompter: &mut P, rng: &mut G, clock: &mut C) -> Result<(), Interruption> {
+        match self {
+            Event::WagonBreaksDown => {
+                out.write_all(EVENT_WAGON_BREAKS_DOWN.as_bytes()).unwrap();
+                trip.reverse(15 + rng.gen_range(0..5));
+                repair_wagon(8, supplies, trip, out);
+            },
+            Event::OxInjuresLeg => {
+                out.write_all(EVENT_OX_INJURES_LEG.as_bytes()).unwrap();
+                trip.reverse(25);
+                supplies.lose_oxen(20);
+            },
+            Event::DaughterBreaksArm => {
+                out.write_all(EVENT_DAUGHTER_BREAKS_ARM.as_bytes()).unwrap();
+                trip.reverse(5 + rng.gen_range(0..4));
+                supplies.use_medicine(2 + rng.gen_range(0..3));
+            },
+            Event::OxWandersOff => {
+                out.write_all(EVENT_OX_WANDERS_OFF.as_bytes()).unwrap();
+                trip.reverse(17);
+            },
+            Event::SonGetsLost => {
+                out.write_all(EVENT_SON_GETS_LOST.as_bytes()).unwrap();
+                trip.reverse(10);
+            },
+            Event::UnsafeWater => {
+                out.write_all(EVENT_UNSAFE_WATER.as_bytes()).unwrap();
+                trip.reverse(2 + rng.gen_range(0..10));
+            },
+            Event::HeavyRains => {
+                out.write_all(EVENT_HEAVY_RAINS.as_bytes()).unwrap();
+                supplies.lose_food(10);
+                supplies.use_ammo(500);
+                supplies.lose_misc(15);
+                trip.reverse(5 + rng.gen_range(0..10));
+            },
+            Event::Bandits => {
+                out.write_all(EVENT_BANDITS.as_bytes()).unwrap();
+                let shot = shoot(out, prompter, rng, clock, marksman)?;
+                let enough_ammo = supplies.use_ammo(20 * shot.response());
+                if !enough_ammo {
+                    out.write_all(EVENT_BANDITS_CASH.as_bytes()).unwrap();
+                    let stolen = supplies.money_left().fraction(2, 3);
+                    supplies.lose_money(stolen);
+                }
+                // Running out of bullets gets you shot, however quick the draw
+                match shot {
+                    ShotOutcome::Bullseye(_) if enough_ammo => out.write_all(EVENT_BANDITS_QUICK_DRAW.as_bytes()).unwrap(),
+                    _ => {
+                        out.write_all(EVENT_BANDITS_SHOT.as_bytes()).unwrap();
+                        health.injure();
+                        supplies.use_medicine(5);
+                        supplies.lose_oxen(20);
+                    },
+                }
+            },
+            Event::WagonFire => {
+                out.write_all(EVENT_WAGON_FIRE.as_bytes()).unwrap();
+                supplies.lose_food(40);
+                supplies.use_ammo(400);
+                supplies.lose_misc(3 + rng.gen_range(0..8));
+                trip.reverse(15);
+            },
+            Event::HeavyFog => {
+                out.write_all(EVENT_HEAVY_FOG.as_bytes()).unwrap();
+                trip.reverse(10 + rng.gen_range(0..5));
+            },
+            Event::PoisonousSnake => {
+                out.write_all(EVENT_POISONOUS_SNAKE.as_bytes()).unwrap();
+                supplies.use_ammo(10);
+                if !supplies.use_medicine(5) {
+                    return Err(Interruption::Died(DeathCause::Snakebite));
+                }
+            },
+            Event::WagonSwamped => {
+                out.write_all(EVENT_WAGON_SWAMPED.as_bytes()).unwrap();
+                supplies.lose_food(30);
+                supplies.lose_clothes(20);
+                trip.reverse(20 + rng.gen_range(0..20));
+            },
+            Event::WildAnimals => {
+                out.write_all(EVENT_WILD_ANIMALS.as_bytes()).unwrap();
+                if supplies.ammo_left() <= HUNT_MIN_AMMO {
+                    out.write_all(EVENT_WILD_ANIMALS_OVERPOWERED.as_bytes()).unwrap();
+                    health.injure();
+                    return Err(Interruption::Died(DeathCause::Injuries));
+                }
+                let shot = shoot(out, prompter, rng, clock, marksman)?;
+                let response = shot.response();
+                if response <= 2 {
+                    out.write_all(EVENT_WILD_ANIMALS_NICE.as_bytes()).unwrap();
+                } else {
+                    out.write_all(EVENT_WILD_ANIMALS_SLOW.as_bytes()).unwrap();
+                }
+                supplies.use_ammo(20 * response);
+                supplies.lose_clothes(4 * response);
+                supplies.lose_food(8 * response);
+            },
+            Event::ColdWeather => {
+                if supplies.clothes_left() >= clothing_required(trip.current_date(), trip.miles_traveled()) {
+                    out.write_all(EVENT_COLD_WEATHER_WARM.as_bytes()).unwrap();
+                } else {
+                    out.write_all(EVENT_COLD_WEATHER_COLD.as_bytes()).unwrap();
+                    return Ok(health.fall_ill(eating, supplies, trip, out, rng)?);
+                }
+            },
+            Event::HailStorm => {
+                out.write_all(EVENT_HAIL_STORM.as_bytes()).unwrap();
+                trip.reverse(5 + rng.gen_range(0..10));
+                supplies.use_ammo(200);
+                supplies.lose_misc(4 + rng.gen_range(0..3));
+            },
+            Event::Illness => {
+                return Ok(health.check_illness(eating, supplies, trip, out, rng)?);
+            },
+            Event::HelpfulIndians => {
+                out.write_all(EVENT_HELPFUL_INDIANS.as_bytes()).unwrap();
+                supplies.add_food(14);
+            },
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply an event, typing the correct word for any shot straight away
+    fn run_event(event: Event, supplies: &mut Supplies, trip: &mut Trip, health: &mut Health) -> (Result<(), Interruption>, String) {
+        run_event_after(Duration::ZERO, event, supplies, trip, health)
+    }
+
+    /// Apply an event, typing the correct word for any shot after `reaction`
+    fn run_event_after(reaction: Duration, event: Event, supplies: &mut Supplies, trip: &mut Trip, health: &mut Health)
+            -> (Result<(), Interruption>, String) {
+        let mut shot = ShotFixture::new(None, reaction);
+        let result = event.apply(supplies, trip, health, &MarksmanQuality::Ace, &EatingQuality::Moderately,
+            &mut shot.cout, &mut shot.prompter, &mut shot.rng, &mut shot.clock);
+        (result, shot.output())
+    }
+
+    #[test]
+    fn test_events_weights() {
+        assert_eq!(100, EVENTS.iter().map(|(_, weight)| weight).sum::<u32>());
+    }
+
+    #[test]
+    fn test_event_roll_covers_table() {
+        let mut rng = StdRng::seed_from_u64(1847);
+        let events: Vec<Event> = (0..2000).map(|i| Event::roll(date(4 + i % 2 * 7, 1), 0, &mut rng)).collect();
+        for (event, _) in EVENTS.iter() {
+            assert!(events.contains(event), "{:?} never rolled", event);
+        }
+    }
+
+    #[test]
+    fn test_event_roll_cold() {
+        let mut rng = StdRng::seed_from_u64(1847);
+        let events: Vec<Event> = (0..2000).map(|_| Event::roll(date(11, 1), 0, &mut rng)).collect();
+        assert!(!events.contains(&Event::HeavyRains));
+    }
+
+    #[test]
+    fn test_event_roll_not_yet_cold() {
+        let mut rng = StdRng::seed_from_u64(1847);
+        let events: Vec<Event> = (0..2000).map(|_| Event::roll(date(7, 1), 1200, &mut rng)).collect();
+        assert!(!events.contains(&Event::ColdWeather));
+    }
+
+    #[test]
+    fn test_repair_wagon() {
+        let mut supplies = outfitted(100, 100, 50, 50);
+        let mut trip = Trip::new();
+        trip.travel(200);
+        let mut cout = Cursor::new(Vec::new());
+        repair_wagon(8, &mut supplies, &mut trip, &mut cout);
+        assert_eq!(42, supplies.misc_left());
+        assert_eq!(8, supplies.misc_used(MiscUse::Repairs));
+        assert_eq!(200, trip.miles_traveled());
+    }
+
+    #[test]
+    fn test_repair_wagon_no_repairs() {
+        let mut supplies = Supplies::new();
+        let mut trip = Trip::new();
+        trip.travel(200);
+        let mut cout = Cursor::new(Vec::new());
+        repair_wagon(8, &mut supplies, &mut trip, &mut cout);
+        assert_eq!(200 - NO_REPAIRS_MILES, trip.miles_traveled());
+        assert_eq!(EVENT_NO_REPAIRS, read_out(&mut cout));
+    }
+
+    #[test]
+    fn test_event_ox_injures_leg() {
+        let mut supplies = outfitted(100, 100, 50, 50);
+        let mut trip = Trip::new();
+        trip.travel(200);
+        let mut health = Health::new();
+        let (result, event_out) = run_event(Event::OxInjuresLeg, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(EVENT_OX_INJURES_LEG, event_out);
+        assert_eq!(175, trip.miles_traveled());
+        assert_eq!(230, supplies.oxen_left());
+    }
+
+    #[test]
+    fn test_event_bandits_quick_draw() {
+        let mut supplies = outfitted(100, 100, 50, 50);
+        let mut trip = Trip::new();
+        let mut health = Health::new();
+        let (result, event_out) = run_event(Event::Bandits, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Ok(()), result);
+        assert!(event_out.ends_with(EVENT_BANDITS_QUICK_DRAW));
+        assert!(!health.is_injured());
+        assert_eq!(100, supplies.ammo_left());
+    }
+
+    #[test]
+    fn test_event_bandits_out_of_ammo() {
+        let mut supplies = outfitted(100, 10, 50, 50);
+        let mut trip = Trip::new();
+        let mut health = Health::new();
+        let (result, event_out) = run_event_after(Duration::from_millis(1400), Event::Bandits, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Ok(()), result);
+        assert!(event_out.contains(EVENT_BANDITS_CASH));
+        assert!(event_out.ends_with(EVENT_BANDITS_SHOT));
+        assert!(health.is_injured());
+        assert_eq!(0, supplies.ammo_left());
+        assert_eq!(Money::from_dollars(83), supplies.money_left());
+    }
+
+    #[test]
+    fn test_event_poisonous_snake() {
+        let mut supplies = outfitted(100, 100, 50, 50);
+        let mut trip = Trip::new();
+        let mut health = Health::new();
+        let (result, _) = run_event(Event::PoisonousSnake, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(45, supplies.misc_left());
+        assert_eq!(5, supplies.misc_used(MiscUse::Medicine));
+        assert_eq!(90, supplies.ammo_left());
+    }
+
+    #[test]
+    fn test_event_poisonous_snake_no_medicine() {
+        let mut supplies = Supplies::new();
+        let mut trip = Trip::new();
+        let mut health = Health::new();
+        let (result, _) = run_event(Event::PoisonousSnake, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Err(Interruption::Died(DeathCause::Snakebite)), result);
+    }
+
+    #[test]
+    fn test_event_wagon_swamped() {
+        let mut supplies = outfitted(100, 100, 50, 50);
+        let mut trip = Trip::new();
+        trip.travel(200);
+        let mut health = Health::new();
+        let (result, event_out) = run_event(Event::WagonSwamped, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(EVENT_WAGON_SWAMPED, event_out);
+        assert_eq!(70, supplies.food_left());
+        assert_eq!(30, supplies.clothes_left());
+        assert!(trip.miles_traveled() <= 180);
+        assert!(trip.miles_traveled() > 160);
+    }
+
+    #[test]
+    fn test_event_wild_animals_overpowered() {
+        let mut supplies = outfitted(0, HUNT_MIN_AMMO, 0, 0);
+        let mut trip = Trip::new();
+        let mut health = Health::new();
+        let (result, event_out) = run_event(Event::WildAnimals, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Err(Interruption::Died(DeathCause::Injuries)), result);
+        assert!(event_out.ends_with(EVENT_WILD_ANIMALS_OVERPOWERED));
+    }
+
+    #[test]
+    fn test_event_wild_animals_nice_shooting() {
+        let mut supplies = outfitted(100, 100, 50, 50);
+        let mut trip = Trip::new();
+        let mut health = Health::new();
+        let (result, event_out) = run_event(Event::WildAnimals, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Ok(()), result);
+        assert!(event_out.ends_with(EVENT_WILD_ANIMALS_NICE));
+        assert_eq!(100, supplies.food_left());
+    }
+
+    #[test]
+    fn test_event_cold_weather_warm() {
+        let mut supplies = outfitted(100, 100, CLOTHES_REQUIRED_MOUNTAINS, 50);
+        let mut trip = trip_after(15, 1000);
+        let mut health = Health::new();
+        let (result, event_out) = run_event(Event::ColdWeather, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(EVENT_COLD_WEATHER_WARM, event_out);
+    }
+
+    #[test]
+    fn test_event_cold_weather_cold() {
+        let mut supplies = outfitted(100, 100, CLOTHES_REQUIRED_MOUNTAINS - 1, 50);
+        let mut trip = trip_after(15, 1000);
+        let mut health = Health::new();
+        let (result, event_out) = run_event(Event::ColdWeather, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Ok(()), result);
+        assert!(event_out.starts_with(EVENT_COLD_WEATHER_COLD));
+        assert!(supplies.misc_left() < 50);
+    }
+
+    #[test]
+    fn test_event_helpful_indians() {
+        let mut supplies = outfitted(100, 100, 50, 50);
+        let mut trip = Trip::new();
+        let mut health = Health::new();
+        let (result, event_out) = run_event(Event::HelpfulIndians, &mut supplies, &mut trip, &mut health);
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(EVENT_HELPFUL_INDIANS, event_out);
+        assert_eq!(114, supplies.food_left());
+    }
+}
@@ -1,47 +1,144 @@
+use std::io::Write;
+use crate::money::*;
+use crate::prompt::*;
+use crate::strings::*;
+use crate::supplies::*;
+use crate::trip::*;
+#[cfg(test)]
 use std::str;
+#[cfg(test)]
 use std::io::{Cursor, Read, Seek, SeekFrom};
-use crate::*;
 
-const ASK_FINISH_MINISTER: &str = "Would you like a minister (y/n)? ";
-const ASK_FINISH_FUNERAL: &str = "Would you like a fancy funeral (y/n)? ";
-const ASK_FINISH_NOTIFY_KIN: &str = "Would you like to inform your next of kin (y/n)? ";
+pub const ASK_FINISH_MINISTER: &str = "Would you like a minister (y/n)? ";
+pub const ASK_FINISH_FUNERAL: &str = "Would you like a fancy funeral (y/n)? ";
+pub const ASK_FINISH_NOTIFY_KIN: &str = "Would you like to inform your next of kin (y/n)? ";
 
-const NOTIFY_TELEGRAPH_CHARGE: &str = "\nThat will be $4.50 for the telegraph charge.\n\n";
+/// What it costs to telegraph the next of kin
+pub const TELEGRAPH_CHARGE: Money = Money::from_cents(450);
+
+macro_rules! NOTIFY_TELEGRAPH_CHARGE { () => { "\nThat will be ${} for the telegraph charge.\n\n" }; }
+const NOTIFY_TELEGRAPH_UNAFFORDABLE: &str = "\nYou don't have enough money left for the telegraph charge.\n";
 const NOTIFY_SADIE_WORRIED: &str = "\nYour Aunt Sadie in St. Louis is really worried about you...\n\n";
 
-pub fn complete_trip<W: Write>(out: &mut W, supplies: &mut Supplies) {
-    let prefix = include_str!("../strings/complete_prefix.txt");
-    let suffix = include_str!("../strings/complete_suffix.txt");
+/// The reason the party didn't make it to Oregon
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum DeathCause {
+    Starvation,
+    Pneumonia,
+    Injuries,
+    Blizzard,
+    Exposure,
+    Riders,
+    Snakebite,
+    OutOfTime,
+}
+
+impl DeathCause {
+    /// Retrieve the epitaph describing how the party met their end
+    pub fn epitaph(&self) -> &'static str {
+        match self {
+            DeathCause::Starvation => DEATH_STARVATION,
+            DeathCause::Pneumonia => DEATH_PNEUMONIA,
+            DeathCause::Injuries => DEATH_INJURIES,
+            DeathCause::Blizzard => DEATH_BLIZZARD,
+            DeathCause::Exposure => DEATH_EXPOSURE,
+            DeathCause::Riders => DEATH_RIDERS,
+            DeathCause::Snakebite => DEATH_SNAKEBITE,
+            DeathCause::OutOfTime => DEATH_OUT_OF_TIME,
+        }
+    }
+}
+
+/// The reason a turn on the trail was cut short
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Interruption {
+    /// The party didn't make it
+    Died(DeathCause),
+    /// The player's answer to a prompt couldn't be read
+    Prompt(PromptError),
+}
+
+impl From<DeathCause> for Interruption {
+    fn from(cause: DeathCause) -> Interruption {
+        Interruption::Died(cause)
+    }
+}
+
+impl From<PromptError> for Interruption {
+    fn from(error: PromptError) -> Interruption {
+        Interruption::Prompt(error)
+    }
+}
+
+macro_rules! COMPLETE_ARRIVAL { () => { "You arrived on \x1B[37;4;1m{}\x1B[0m after {} days on the trail\n\n" }; }
+
+macro_rules! COMPLETE_MISC_USED { () => { "Miscellaneous supplies used: {} on repairs, {} on medicine, {} lost along the way\n\n" }; }
+
+pub fn complete_trip<W: Write>(out: &mut W, supplies: &mut Supplies, trip: &mut Trip) {
+    let prefix = COMPLETE_PREFIX;
+    let suffix = COMPLETE_SUFFIX;
+    let arrival = format!(COMPLETE_ARRIVAL!(), trip.arrival_date().format("%A, %B %-d %Y"), trip.trip_days());
     let supplies_status = format!("Supplies left:\n{}\n", supplies);
-    out.write(prefix.as_bytes()).unwrap();
-    out.write(supplies_status.as_bytes()).unwrap();
-    out.write(suffix.as_bytes()).unwrap();
+    let misc_summary = format!(COMPLETE_MISC_USED!(), supplies.misc_used(MiscUse::Repairs),
+        supplies.misc_used(MiscUse::Medicine), supplies.misc_used(MiscUse::Lost));
+    out.write_all(prefix.as_bytes()).unwrap();
+    out.write_all(arrival.as_bytes()).unwrap();
+    out.write_all(supplies_status.as_bytes()).unwrap();
+    out.write_all(misc_summary.as_bytes()).unwrap();
+    out.write_all(suffix.as_bytes()).unwrap();
+    out.flush().unwrap();
+}
+
+macro_rules! ABANDONED_TRIP { () => { "\nGame abandoned on {} after {} miles, {} miles short of Oregon City\n\nSupplies left:\n{}\n" }; }
+/// Shown when the game is abandoned while the party is still outfitting in Independence
+pub const ABANDONED_IN_INDEPENDENCE: &str = "\nGame abandoned before leaving Independence\n";
+
+/// Sum up where the trip was abandoned, and what the party had left
+pub fn abandon_trip<W: Write>(out: &mut W, supplies: &mut Supplies, trip: &mut Trip) {
+    let miles = trip.miles_traveled();
+    let abandoned = format!(ABANDONED_TRIP!(), trip.current_date().format("%A, %B %-d %Y"), miles,
+        OREGON_MILES.saturating_sub(miles), supplies);
+    out.write_all(abandoned.as_bytes()).unwrap();
     out.flush().unwrap();
 }
 
-pub fn handle_death<W: Write, R: BufRead>(out: &mut W, input: &mut R) {
-    out.write(include_str!("../strings/death_prefix.txt").as_bytes()).unwrap();
-    let _ = ask_yn!(ASK_FINISH_MINISTER, out, input);
-    let _ = ask_yn!(ASK_FINISH_FUNERAL, out, input);
-    let notify_sadie = ask_yn!(ASK_FINISH_NOTIFY_KIN, out, input);
-    
-    if notify_sadie {
-        out.write(NOTIFY_TELEGRAPH_CHARGE.as_bytes()).unwrap();
+/// Lay the party to rest, charging for the telegraph if the next of kin are to be told
+pub fn handle_death<W: Write, P: Prompter>(cause: DeathCause, supplies: &mut Supplies, out: &mut W,
+        prompter: &mut P) -> Result<(), PromptError> {
+    out.write_all(cause.epitaph().as_bytes()).unwrap();
+    out.write_all(DEATH_PREFIX.as_bytes()).unwrap();
+    prompter.yes_no(ASK_FINISH_MINISTER, out)?;
+    prompter.yes_no(ASK_FINISH_FUNERAL, out)?;
+    let notify_sadie = prompter.yes_no(ASK_FINISH_NOTIFY_KIN, out)?;
+
+    if !notify_sadie {
+        out.write_all(NOTIFY_SADIE_WORRIED.as_bytes()).unwrap();
+    } else if supplies.pay(TELEGRAPH_CHARGE).is_ok() {
+        out.write_all(format!(NOTIFY_TELEGRAPH_CHARGE!(), TELEGRAPH_CHARGE).as_bytes()).unwrap();
     } else {
-        out.write(NOTIFY_SADIE_WORRIED.as_bytes()).unwrap();
+        out.write_all(NOTIFY_TELEGRAPH_UNAFFORDABLE.as_bytes()).unwrap();
+        out.write_all(NOTIFY_SADIE_WORRIED.as_bytes()).unwrap();
     }
-    out.write(include_str!("../strings/death_suffix.txt").as_bytes()).unwrap();
+    out.write_all(DEATH_SUFFIX.as_bytes()).unwrap();
+    Ok(())
 }
 
 #[test]
 fn test_complete_trip() {
     let mut supplies = Supplies::new();
-    let prefix = include_str!("../strings/complete_prefix.txt");
-    let suffix = include_str!("../strings/complete_suffix.txt");
-    let trip_message = format!("{}Supplies left:\n{}\n{}", prefix, supplies, suffix);
+    let mut trip = Trip::new();
+    let prefix = COMPLETE_PREFIX;
+    let suffix = COMPLETE_SUFFIX;
+    let misc_summary = format!(COMPLETE_MISC_USED!(), 0, 0, 0);
+    let trip_message = format!("{}You arrived on \x1B[37;4;1mMonday, March 29 1847\x1B[0m after 0 days on the trail\n\nSupplies left:\n{}\n{}{}",
+        prefix, supplies, misc_summary, suffix);
     let mut c = Cursor::new(Vec::new());
 
-    complete_trip(&mut c, &mut supplies);
+    complete_trip(&mut c, &mut supplies, &mut trip);
     c.seek(SeekFrom::Start(0)).unwrap();
     let mut trip_out = Vec::new();
     c.read_to_end(&mut trip_out).unwrap();
@@ -49,42 +146,98 @@ fn test_complete_trip() {
     assert_eq!(trip_message, str::from_utf8(&trip_out).unwrap());
 }
 
+#[test]
+fn test_abandon_trip() {
+    let mut supplies = Supplies::new();
+    let mut trip = Trip::new();
+    trip.travel(640);
+    let mut cout = Cursor::new(Vec::new());
+
+    abandon_trip(&mut cout, &mut supplies, &mut trip);
+    let trip_out = String::from_utf8(cout.into_inner()).unwrap();
+    assert_eq!(format!(ABANDONED_TRIP!(), "Monday, March 29 1847", 640, 1400, supplies), trip_out);
+}
+
 #[test]
 fn test_handle_death_notify() {
-    let prefix = include_str!("../strings/death_prefix.txt");
-    let suffix = include_str!("../strings/death_suffix.txt");
-    let trip_message = format!("{}{}{}{}{}{}",
-        prefix, ASK_FINISH_MINISTER, ASK_FINISH_FUNERAL, ASK_FINISH_NOTIFY_KIN, NOTIFY_TELEGRAPH_CHARGE, suffix);
+    let prefix = DEATH_PREFIX;
+    let suffix = DEATH_SUFFIX;
+    let telegraph_charge = format!(NOTIFY_TELEGRAPH_CHARGE!(), "4.50");
+    let trip_message = format!("{}{}{}{}{}{}{}",
+        DeathCause::Starvation.epitaph(), prefix, ASK_FINISH_MINISTER, ASK_FINISH_FUNERAL, ASK_FINISH_NOTIFY_KIN, telegraph_charge, suffix);
 
+    let mut supplies = Supplies::new();
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"y\r\ny\r\ny").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
+    let mut prompter = ScriptedPrompter::new(&["y", "y", "y"]);
     // Assert
-    handle_death(&mut cout, &mut cin);
+    handle_death(DeathCause::Starvation, &mut supplies, &mut cout, &mut prompter).unwrap();
     cout.seek(SeekFrom::Start(0)).unwrap();
     let mut trip_out = Vec::new();
     cout.read_to_end(&mut trip_out).unwrap();
 
     assert_eq!(trip_message, str::from_utf8(&trip_out).unwrap());
+    assert_eq!(Money::from_cents(69550), supplies.money_left());
+}
+
+#[test]
+fn test_handle_death_notify_unaffordable() {
+    let mut supplies = Supplies::new();
+    supplies.pay(Money::from_dollars(696)).unwrap();
+    let mut cout = Cursor::new(Vec::new());
+    let mut prompter = ScriptedPrompter::new(&["y", "y", "y"]);
+    // Assert
+    handle_death(DeathCause::Starvation, &mut supplies, &mut cout, &mut prompter).unwrap();
+    cout.seek(SeekFrom::Start(0)).unwrap();
+    let mut trip_out = Vec::new();
+    cout.read_to_end(&mut trip_out).unwrap();
+
+    let trip_out = str::from_utf8(&trip_out).unwrap();
+    assert!(trip_out.contains(&format!("{}{}", NOTIFY_TELEGRAPH_UNAFFORDABLE, NOTIFY_SADIE_WORRIED)));
+    assert_eq!(Money::from_dollars(4), supplies.money_left());
 }
 
 #[test]
 fn test_handle_death_no_notify() {
-    let prefix = include_str!("../strings/death_prefix.txt");
-    let suffix = include_str!("../strings/death_suffix.txt");
-    let trip_message = format!("{}{}{}{}{}{}",
-        prefix, ASK_FINISH_MINISTER, ASK_FINISH_FUNERAL, ASK_FINISH_NOTIFY_KIN, NOTIFY_SADIE_WORRIED, suffix);
+    let prefix = DEATH_PREFIX;
+    let suffix = DEATH_SUFFIX;
+    let trip_message = format!("{}{}{}{}{}{}{}",
+        DeathCause::Starvation.epitaph(), prefix, ASK_FINISH_MINISTER, ASK_FINISH_FUNERAL, ASK_FINISH_NOTIFY_KIN, NOTIFY_SADIE_WORRIED, suffix);
 
+    let mut supplies = Supplies::new();
     let mut cout = Cursor::new(Vec::new());
-    let mut cin = Cursor::new(Vec::new());
-    cin.write(b"y\r\ny\r\nn").unwrap();
-    cin.seek(SeekFrom::Start(0)).unwrap();
+    let mut prompter = ScriptedPrompter::new(&["y", "y", "n"]);
     // Assert
-    handle_death(&mut cout, &mut cin);
+    handle_death(DeathCause::Starvation, &mut supplies, &mut cout, &mut prompter).unwrap();
     cout.seek(SeekFrom::Start(0)).unwrap();
     let mut trip_out = Vec::new();
     cout.read_to_end(&mut trip_out).unwrap();
 
     assert_eq!(trip_message, str::from_utf8(&trip_out).unwrap());
-}
\ No newline at end of file
+    assert_eq!(Money::from_dollars(700), supplies.money_left());
+}
+#[test]
+fn test_handle_death_epitaph() {
+    let mut cout = Cursor::new(Vec::new());
+    let mut prompter = ScriptedPrompter::new(&["n", "n", "n"]);
+    // Assert
+    handle_death(DeathCause::Snakebite, &mut Supplies::new(), &mut cout, &mut prompter).unwrap();
+    cout.seek(SeekFrom::Start(0)).unwrap();
+    let mut trip_out = Vec::new();
+    cout.read_to_end(&mut trip_out).unwrap();
+
+    let trip_out = str::from_utf8(&trip_out).unwrap();
+    assert!(trip_out.starts_with("You died of snakebite since you have no medicine.\n"));
+    assert!(!trip_out.contains(DeathCause::Starvation.epitaph()));
+}
+
+#[test]
+fn test_death_cause_epitaphs_differ() {
+    let causes = [DeathCause::Starvation, DeathCause::Pneumonia, DeathCause::Injuries, DeathCause::Blizzard,
+        DeathCause::Exposure, DeathCause::Riders, DeathCause::Snakebite, DeathCause::OutOfTime];
+    for (i, cause) in causes.iter().enumerate() {
+        assert!(!cause.epitaph().is_empty());
+        for other in causes.iter().skip(i + 1) {
+            assert_ne!(cause.epitaph(), other.epitaph());
+        }
+    }
+}
@@ -0,0 +1,539 @@
+use std::io::{Write, BufRead};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use crate::ask::*;
+use crate::clock::*;
+use crate::eating::*;
+use crate::events::*;
+use crate::finish::*;
+use crate::hunt::*;
+use crate::illness::*;
+use crate::marksman::*;
+use crate::mountains::*;
+use crate::prompt::*;
+use crate::riders::*;
+use crate::save::*;
+use crate::store::*;
+use crate::supplies::*;
+use crate::trip::*;
+use crate::weather::*;
+#[cfg(test)]
+use std::io::Cursor;
+#[cfg(test)]
+use chrono::NaiveDate;
+
+/// Stopping at a fort takes time away from the trail
+pub const FORT_MILES: u32 = 45;
+/// Below this much food the party is warned to find more
+pub const LOW_FOOD: u32 = 12;
+
+const GAME_NO_FORT: &str = "You haven't reached a fort yet\n";
+const GAME_LOW_FOOD: &str = "You'd better do some hunting or buy some food, and soon!!!!\n";
+macro_rules! GAME_DEADLINE_WARNING { () => { "Snow will soon close the mountain passes---you have {} turns left to reach Oregon City!\n" }; }
+macro_rules! GAME_STATUS { () => { "Total mileage traveled: {}\nIt is now {} ({:?})\nSupplies remaining:\n{}\n" }; }
+
+/// Where the game stands
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum GameStatus {
+    /// Still on the trail
+    InProgress,
+    /// Made it to Oregon City
+    Won,
+    /// The party didn't make it
+    Died(DeathCause),
+    /// The player quit partway along the trail (the input ended)
+    Quit,
+}
+
+/// The result of trying to play a turn
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum TurnOutcome {
+    /// The action can't be taken right now (no fort nearby, the order at the fort cancelled, or not enough bullets to hunt), another must be chosen
+    ///     Saving is left to the frontend, which knows where to save the game
+    Unavailable,
+    /// The turn was played, leaving the game in this status
+    Played(GameStatus),
+}
+
+/// A game of Oregon Trail, from setting out with an outfitted wagon through to the end of the trail
+///     The game reads and writes through whatever streams it is given, so it can be driven without a terminal
+pub struct Game<G: Rng, C: Clock> {
+    supplies: Supplies,
+    trip: Trip,
+    marksman: MarksmanQuality,
+    health: Health,
+    mountains: Mountains,
+    forts: Vec<Store>,
+    fort_available: bool,
+    eating: EatingQuality,
+    status: GameStatus,
+    rng: G,
+    clock: C,
+}
+
+impl<G: Rng, C: Clock> Game<G, C> {
+    /// Constructor
+    ///     The party sets out from Independence with the supplies they bought there
+    pub fn new(marksman: MarksmanQuality, supplies: Supplies, rng: G, clock: C) -> Game<G, C> {
+        Game {
+            supplies,
+            trip: Trip::new(),
+            marksman,
+            health: Health::new(),
+            mountains: Mountains::new(),
+            forts: Store::forts(),
+            fort_available: false,
+            eating: EatingQuality::Moderately,
+            status: GameStatus::InProgress,
+            rng,
+            clock,
+        }
+    }
+
+    /// Retrieve where the game stands
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    /// Can the party stop at a fort this turn?
+    pub fn fort_available(&self) -> bool {
+        self.fort_available
+    }
+
+    /// Report the state of the trip at the start of a turn, with any warnings
+    pub fn report<W: Write>(&mut self, out: &mut W) {
+        if self.trip.turns_left() <= DEADLINE_WARNING_TURNS {
+            out.write_all(format!(GAME_DEADLINE_WARNING!(), self.trip.turns_left()).as_bytes()).unwrap();
+        }
+        if self.supplies.food_left() <= LOW_FOOD {
+            out.write_all(GAME_LOW_FOOD.as_bytes()).unwrap();
+        }
+        out.write_all(format!(GAME_STATUS!(), self.trip.miles_traveled(), self.trip.current_date().format("%A %d-%b-%Y"),
+            self.trip.season(), self.supplies).as_bytes()).unwrap();
+        out.flush().unwrap();
+    }
+
+    /// Play a turn of the game, taking an action and then traveling the next leg of the trail
+    ///     Anything else the turn needs to know (how well to eat, shooting, tactics) is asked of the prompter
+    ///     Once the game is over no more turns are played, its final status is returned
+    pub fn step<W: Write, P: Prompter>(&mut self, action: TurnAction, out: &mut W, prompter: &mut P) -> Result<TurnOutcome, PromptError> {
+        if self.status != GameStatus::InProgress {
+            return Ok(TurnOutcome::Played(self.status));
+        }
+        let played = match self.act(action, out, prompter) {
+            Ok(false) => return Ok(TurnOutcome::Unavailable),
+            Ok(true) => self.travel(out, prompter),
+            Err(interruption) => Err(interruption),
+        };
+
+        self.status = match played {
+            Ok(()) => self.next_turn(out),
+            Err(Interruption::Died(cause)) => GameStatus::Died(cause),
+            Err(Interruption::Prompt(PromptError::EndOfInput)) => GameStatus::Quit,
+            Err(Interruption::Prompt(error)) => return Err(error),
+        };
+        Ok(TurnOutcome::Played(self.status))
+    }
+
+    /// Quit the game partway along the trail, such as when there's no more input to play it with
+    pub fn quit(&mut self) {
+        if self.status == GameStatus::InProgress {
+            self.status = GameStatus::Quit;
+        }
+    }
+
+    /// Take the turn's action, false if it can't be taken right now
+    fn act<W: Write, P: Prompter>(&mut self, action: TurnAction, out: &mut W, prompter: &mut P) -> Result<bool, Interruption> {
+        match action {
+            TurnAction::Fort => {
+                if !self.fort_available {
+                    return Ok(false);
+                }
+                let miles = self.trip.miles_traveled();
+                match nearest_fort(&mut self.forts, miles) {
+                    // Cancelling the order leaves the party free to do something else with the turn
+                    Some(fort) => if !self.supplies.buy(fort, out, prompter)? {
+                        return Ok(false);
+                    },
+                    None => {
+                        out.write_all(GAME_NO_FORT.as_bytes()).unwrap();
+                        return Ok(false);
+                    },
+                }
+                self.fort_available = false;
+                self.trip.reverse(FORT_MILES);
+            },
+            TurnAction::Hunt => {
+                let result = hunt(&mut self.supplies, &mut self.trip, &self.marksman, out, prompter, &mut self.rng, &mut self.clock)?;
+                if result == HuntResult::NotEnoughAmmo {
+                    return Ok(false);
+                }
+            },
+            TurnAction::Continue => {
+                self.fort_available = true;
+            },
+            TurnAction::SaveAndQuit => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Eat and travel the next leg of the trail, surviving whatever happens along the way
+    fn travel<W: Write, P: Prompter>(&mut self, out: &mut W, prompter: &mut P) -> Result<(), Interruption> {
+        if self.supplies.food_left() < EatingQuality::Poorly.food_eaten() {
+            return Err(Interruption::Died(DeathCause::Starvation));
+        }
+        self.eating = loop {
+            let eating = ask_eat(out, prompter)?;
+            match self.supplies.eat(eating.food_eaten()) {
+                Ok(_) => break eating,
+                Err(e) => out.write_all(format!("{}\n", e).as_bytes()).unwrap(),
+            }
+        };
+
+        self.trip.turn(self.supplies.oxen_condition(), &mut self.rng);
+
+        // Riders may be waiting along the trail
+        if self.rng.gen_bool(riders_chance(self.trip.miles_traveled())) {
+            riders(&mut self.supplies, &mut self.trip, &mut self.health, &self.marksman,
+                out, prompter, &mut self.rng, &mut self.clock)?;
+        }
+
+        // Something always happens along the trail
+        let event = Event::roll(self.trip.current_date(), self.trip.miles_traveled(), &mut self.rng);
+        event.apply(&mut self.supplies, &mut self.trip, &mut self.health, &self.marksman, &self.eating,
+            out, prompter, &mut self.rng, &mut self.clock)?;
+
+        self.mountains.cross(&mut self.supplies, &mut self.trip, &mut self.health, &self.eating, out, &mut self.rng)?;
+        cold_weather(&mut self.supplies, &mut self.trip, &mut self.health, &self.eating, out, &mut self.rng)?;
+        Ok(())
+    }
+
+    /// See where the trail has left the party, ready for the next turn
+    fn next_turn<W: Write>(&mut self, out: &mut W) -> GameStatus {
+        if self.trip.arrived() {
+            // Only part of the last turn was needed, so not all of that turn's food was eaten
+            self.supplies.add_food(self.trip.final_turn_unused(self.eating.food_eaten()));
+            return GameStatus::Won;
+        }
+        // Snow closes the mountain passes for the winter
+        if self.trip.out_of_time() {
+            return GameStatus::Died(DeathCause::OutOfTime);
+        }
+        // Anyone sick or injured must see the doctor
+        match self.health.see_doctor(&mut self.supplies, out) {
+            Ok(()) => GameStatus::InProgress,
+            Err(cause) => GameStatus::Died(cause),
+        }
+    }
+
+    /// Close out a finished game, with the arrival in Oregon City, the funeral, or where the trip was abandoned
+    pub fn finish<W: Write, P: Prompter>(&mut self, out: &mut W, prompter: &mut P) -> Result<(), PromptError> {
+        match self.status {
+            GameStatus::InProgress => Ok(()),
+            GameStatus::Won => {
+                complete_trip(out, &mut self.supplies, &mut self.trip);
+                Ok(())
+            },
+            GameStatus::Died(cause) => match handle_death(cause, &mut self.supplies, out, prompter) {
+                // The game is already over, running out of input only cuts the funeral short
+                Err(PromptError::EndOfInput) => Ok(()),
+                result => result,
+            },
+            GameStatus::Quit => {
+                abandon_trip(out, &mut self.supplies, &mut self.trip);
+                Ok(())
+            },
+        }
+    }
+}
+
+impl<C: Clock> Game<ChaCha12Rng, C> {
+    /// Save the game between turns, so it can be resumed exactly where it was left
+    ///     The random number generator is saved part way through its stream, so the loaded game rolls what this one would have
+    pub fn save<W: Write>(&self, out: &mut W) -> Result<(), SaveError> {
+        let mut file = SaveFile::new();
+        let seed: String = self.rng.get_seed().iter().map(|byte| format!("{:02x}", byte)).collect();
+        file.set("game.rng.seed", seed);
+        file.set("game.rng.stream", self.rng.get_stream());
+        file.set("game.rng.word_pos", self.rng.get_word_pos());
+        file.set("game.marksman", self.marksman.rating());
+        file.set("game.eating", self.eating.value());
+        file.set("game.fort_available", self.fort_available);
+        self.supplies.save(&mut file);
+        self.trip.save(&mut file);
+        self.health.save(&mut file);
+        self.mountains.save(&mut file);
+        for (fort, store) in self.forts.iter().enumerate() {
+            store.save(&mut file, &format!("forts.{}", fort));
+        }
+        file.write(out)
+    }
+
+    /// Load a saved game, ready to play the next turn
+    ///     A marksman or eating value the game never saves is rejected rather than played as something else
+    pub fn load<R: BufRead>(input: R, clock: C) -> Result<Game<ChaCha12Rng, C>, SaveError> {
+        let file = SaveFile::read(input)?;
+        let marksman = MarksmanQuality::from_u32(file.get("game.marksman")?);
+        if marksman == MarksmanQuality::Unknown {
+            return Err(SaveError::Invalid("game.marksman".to_string(), file.text("game.marksman")?.to_string()));
+        }
+        let eating = file.get("game.eating")?;
+        let eating = EatingQuality::from_u32(eating)
+            .ok_or_else(|| SaveError::Invalid("game.eating".to_string(), eating.to_string()))?;
+        let mut forts = Store::forts();
+        for (fort, store) in forts.iter_mut().enumerate() {
+            store.load(&file, &format!("forts.{}", fort))?;
+        }
+        Ok(Game {
+            supplies: Supplies::load(&file)?,
+            trip: Trip::load(&file)?,
+            marksman,
+            health: Health::load(&file)?,
+            mountains: Mountains::load(&file)?,
+            forts,
+            fort_available: file.get("game.fort_available")?,
+            eating,
+            status: GameStatus::InProgress,
+            rng: load_rng(&file)?,
+            clock,
+        })
+    }
+}
+
+/// Load the random number generator, as saved, part way through its stream
+fn load_rng(file: &SaveFile) -> Result<ChaCha12Rng, SaveError> {
+    let text = file.text("game.rng.seed")?;
+    let invalid = || SaveError::Invalid("game.rng.seed".to_string(), text.to_string());
+    let mut seed = <ChaCha12Rng as SeedableRng>::Seed::default();
+    if text.len() != seed.len() * 2 {
+        return Err(invalid());
+    }
+    for (byte, hex) in seed.iter_mut().zip(text.as_bytes().chunks(2)) {
+        *byte = std::str::from_utf8(hex).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()).ok_or_else(invalid)?;
+    }
+    let mut rng = ChaCha12Rng::from_seed(seed);
+    rng.set_stream(file.get("game.rng.stream")?);
+    rng.set_word_pos(file.get("game.rng.word_pos")?);
+    Ok(rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    fn new_game(supplies: Supplies) -> Game<ChaCha12Rng, ScriptedClock> {
+        Game::new(MarksmanQuality::Ace, supplies, ChaCha12Rng::seed_from_u64(1847), ScriptedClock::new(&[]))
+    }
+
+    /// Play a turn, answering every question the turn asks with a 2 (eat moderately, attack, ...)
+    fn run_step(game: &mut Game<ChaCha12Rng, ScriptedClock>, action: TurnAction) -> (TurnOutcome, String) {
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = ScriptedPrompter::new(&["2"; 20]);
+        let outcome = game.step(action, &mut cout, &mut prompter).unwrap();
+        (outcome, read_out(&mut cout))
+    }
+
+    #[test]
+    fn test_game_new() {
+        let game = new_game(outfitted(100, 500, 50, 50));
+        assert_eq!(GameStatus::InProgress, game.status());
+        assert!(!game.fort_available());
+    }
+
+    #[test]
+    fn test_game_report() {
+        let mut game = new_game(outfitted(10, 500, 50, 50));
+        let mut cout = Cursor::new(Vec::new());
+        game.report(&mut cout);
+        let report = String::from_utf8(cout.into_inner()).unwrap();
+        assert!(report.starts_with(GAME_LOW_FOOD));
+        assert!(report.contains("Total mileage traveled: 0\nIt is now Monday 29-Mar-1847 (Spring)\n"));
+    }
+
+    #[test]
+    fn test_game_fort_unavailable() {
+        let mut game = new_game(outfitted(100, 500, 50, 50));
+        let (outcome, _) = run_step(&mut game, TurnAction::Fort);
+        assert_eq!(TurnOutcome::Unavailable, outcome);
+        assert_eq!(0, game.trip.miles_traveled());
+    }
+
+    #[test]
+    fn test_game_no_fort_reached() {
+        let mut game = new_game(outfitted(100, 500, 50, 50));
+        game.fort_available = true;
+        let (outcome, step_out) = run_step(&mut game, TurnAction::Fort);
+        assert_eq!(TurnOutcome::Unavailable, outcome);
+        assert_eq!(GAME_NO_FORT, step_out);
+        assert!(game.fort_available());
+        assert_eq!(0, game.trip.miles_traveled());
+    }
+
+    #[test]
+    fn test_game_fort_cancelled() {
+        let mut game = new_game(outfitted(100, 500, 50, 50));
+        game.trip.travel(300);
+        game.fort_available = true;
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = ScriptedPrompter::new(&["10", "0", "0", "0", "3"]);
+        let outcome = game.step(TurnAction::Fort, &mut cout, &mut prompter).unwrap();
+        assert_eq!(TurnOutcome::Unavailable, outcome);
+        assert!(game.fort_available());
+        assert_eq!(300, game.trip.miles_traveled());
+        assert_eq!(100, game.supplies.food_left());
+    }
+
+    #[test]
+    fn test_game_hunt_without_bullets() {
+        let mut game = new_game(outfitted(100, 0, 50, 50));
+        let (outcome, _) = run_step(&mut game, TurnAction::Hunt);
+        assert_eq!(TurnOutcome::Unavailable, outcome);
+    }
+
+    #[test]
+    fn test_game_continue() {
+        let mut game = new_game(outfitted(100, 500, 50, 50));
+        let (outcome, step_out) = run_step(&mut game, TurnAction::Continue);
+        assert_eq!(TurnOutcome::Played(GameStatus::InProgress), outcome);
+        assert!(step_out.starts_with("Do you want to eat"));
+        assert!(game.fort_available());
+        assert!(game.trip.miles_traveled() > 0);
+        assert_eq!(NaiveDate::from_ymd_opt(1847, 4, 12).unwrap(), game.trip.current_date());
+    }
+
+    #[test]
+    fn test_game_starves() {
+        let mut game = new_game(outfitted(0, 500, 50, 50));
+        let (outcome, _) = run_step(&mut game, TurnAction::Continue);
+        assert_eq!(TurnOutcome::Played(GameStatus::Died(DeathCause::Starvation)), outcome);
+
+        // Once the game is over, no more turns are played
+        let (outcome, step_out) = run_step(&mut game, TurnAction::Continue);
+        assert_eq!(TurnOutcome::Played(GameStatus::Died(DeathCause::Starvation)), outcome);
+        assert!(step_out.is_empty());
+    }
+
+    #[test]
+    fn test_game_won() {
+        let mut game = new_game(outfitted(100, 500, 50, 50));
+        game.trip.travel(OREGON_MILES);
+        let (outcome, _) = run_step(&mut game, TurnAction::Continue);
+        assert_eq!(TurnOutcome::Played(GameStatus::Won), outcome);
+        assert_eq!(GameStatus::Won, game.status());
+    }
+
+    #[test]
+    fn test_game_quit_end_of_input() {
+        let mut game = new_game(outfitted(100, 500, 50, 50));
+        let mut cout = Cursor::new(Vec::new());
+        let outcome = game.step(TurnAction::Continue, &mut cout, &mut ScriptedPrompter::new(&[]));
+        assert_eq!(Ok(TurnOutcome::Played(GameStatus::Quit)), outcome);
+        assert_eq!(GameStatus::Quit, game.status());
+
+        let mut cout = Cursor::new(Vec::new());
+        game.finish(&mut cout, &mut ScriptedPrompter::new(&[])).unwrap();
+        let finish_out = String::from_utf8(cout.into_inner()).unwrap();
+        assert!(finish_out.starts_with("\nGame abandoned on Monday, March 29 1847 after 0 miles"));
+    }
+
+    #[test]
+    fn test_game_quit() {
+        let mut game = new_game(outfitted(0, 500, 50, 50));
+        game.quit();
+        assert_eq!(GameStatus::Quit, game.status());
+
+        // A game that's already over stays over
+        let mut game = new_game(outfitted(0, 500, 50, 50));
+        run_step(&mut game, TurnAction::Continue);
+        game.quit();
+        assert_eq!(GameStatus::Died(DeathCause::Starvation), game.status());
+    }
+
+    #[test]
+    fn test_game_funeral_end_of_input() {
+        let mut game = new_game(outfitted(0, 500, 50, 50));
+        run_step(&mut game, TurnAction::Continue);
+        let mut cout = Cursor::new(Vec::new());
+        assert_eq!(Ok(()), game.finish(&mut cout, &mut ScriptedPrompter::new(&["y"])));
+    }
+
+    #[test]
+    fn test_game_save_and_quit_unavailable() {
+        let mut game = new_game(outfitted(100, 500, 50, 50));
+        let (outcome, step_out) = run_step(&mut game, TurnAction::SaveAndQuit);
+        assert_eq!(TurnOutcome::Unavailable, outcome);
+        assert!(step_out.is_empty());
+        assert_eq!(GameStatus::InProgress, game.status());
+    }
+
+    #[test]
+    fn test_game_save_and_load() {
+        let mut game = new_game(outfitted(100, 500, 50, 50));
+        run_step(&mut game, TurnAction::Continue);
+        let mut saved = Vec::new();
+        game.save(&mut saved).unwrap();
+
+        let loaded: Game<ChaCha12Rng, ScriptedClock> = Game::load(Cursor::new(saved.clone()), ScriptedClock::new(&[])).unwrap();
+        let mut resaved = Vec::new();
+        loaded.save(&mut resaved).unwrap();
+        assert_eq!(String::from_utf8(saved.clone()).unwrap(), String::from_utf8(resaved).unwrap());
+        assert_eq!(MarksmanQuality::Ace, loaded.marksman);
+        assert!(loaded.fort_available());
+
+        // The saved game and the loaded game carry on exactly the same
+        let mut game: Game<ChaCha12Rng, ScriptedClock> = Game::load(Cursor::new(saved.clone()), ScriptedClock::new(&[])).unwrap();
+        let mut loaded: Game<ChaCha12Rng, ScriptedClock> = Game::load(Cursor::new(saved), ScriptedClock::new(&[])).unwrap();
+        assert_eq!(run_step(&mut game, TurnAction::Continue), run_step(&mut loaded, TurnAction::Continue));
+        assert_eq!(game.trip.current_date(), loaded.trip.current_date());
+        assert_eq!(game.trip.miles_traveled(), loaded.trip.miles_traveled());
+    }
+
+    #[test]
+    fn test_game_save_continues_rng() {
+        let mut unsaved = new_game(outfitted(100, 500, 50, 50));
+        let mut game = new_game(outfitted(100, 500, 50, 50));
+        run_step(&mut unsaved, TurnAction::Continue);
+        run_step(&mut game, TurnAction::Continue);
+        let mut saved = Vec::new();
+        game.save(&mut saved).unwrap();
+        let mut loaded: Game<ChaCha12Rng, ScriptedClock> = Game::load(Cursor::new(saved), ScriptedClock::new(&[])).unwrap();
+
+        // Saving doesn't change what the game rolls next, and the loaded game rolls the same
+        for _ in 0..3 {
+            let played = run_step(&mut unsaved, TurnAction::Continue);
+            assert_eq!(played, run_step(&mut game, TurnAction::Continue));
+            assert_eq!(played, run_step(&mut loaded, TurnAction::Continue));
+        }
+        let roll = unsaved.rng.gen::<u64>();
+        assert_eq!(roll, game.rng.gen::<u64>());
+        assert_eq!(roll, loaded.rng.gen::<u64>());
+    }
+
+    #[test]
+    fn test_game_load_invalid_marksman() {
+        let game = new_game(outfitted(100, 500, 50, 50));
+        let mut saved = Vec::new();
+        game.save(&mut saved).unwrap();
+        let saved = String::from_utf8(saved).unwrap();
+
+        for (from, to) in [("game.marksman=1", "game.marksman=0"), ("game.marksman=1", "game.marksman=99")] {
+            let corrupt = saved.replace(from, to);
+            let result: Result<Game<ChaCha12Rng, ScriptedClock>, SaveError> = Game::load(Cursor::new(corrupt), ScriptedClock::new(&[]));
+            assert_eq!(Some(SaveError::Invalid("game.marksman".to_string(), to["game.marksman=".len()..].to_string())), result.err());
+        }
+
+        let corrupt = saved.replace("game.eating=2", "game.eating=7");
+        let result: Result<Game<ChaCha12Rng, ScriptedClock>, SaveError> = Game::load(Cursor::new(corrupt), ScriptedClock::new(&[]));
+        assert_eq!(Some(SaveError::Invalid("game.eating".to_string(), "7".to_string())), result.err());
+
+        let corrupt = saved.lines().map(|line| if line.starts_with("game.rng.seed=") { "game.rng.seed=0g" } else { line })
+            .collect::<Vec<&str>>().join("\n");
+        let result: Result<Game<ChaCha12Rng, ScriptedClock>, SaveError> = Game::load(Cursor::new(corrupt), ScriptedClock::new(&[]));
+        assert_eq!(Some(SaveError::Invalid("game.rng.seed".to_string(), "0g".to_string())), result.err());
+    }
+}
@@ -0,0 +1,141 @@
+use std::io::Write;
+use rand::Rng;
+use crate::supplies::*;
+use crate::trip::*;
+use crate::marksman::*;
+use crate::shooting::*;
+use crate::clock::*;
+use crate::prompt::*;
+#[cfg(test)]
+use crate::testing::*;
+#[cfg(test)]
+use std::time::Duration;
+
+/// You must have more than this many bullets to go hunting (or to fight off wild animals)
+pub const HUNT_MIN_AMMO: u32 = 39;
+/// Hunting takes time away from the trail
+pub const HUNT_MILES: u32 = 45;
+
+const HUNT_NEED_BULLETS: &str = "Tough---you need more bullets to go hunting\n";
+const HUNT_BIG_ONE: &str = "Right between the eyes---you got a big one!!!!\nFull bellies tonight!\n";
+const HUNT_NICE_SHOT: &str = "Nice shot--right on target--good eatin' tonight!!\n";
+const HUNT_MISSED: &str = "You missed---and your dinner got away.....\n";
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub enum HuntResult {
+    NotEnoughAmmo,
+    Missed,
+    NiceShot,
+    BigOne,
+}
+
+/// Go hunting for food, a "Hunt" action of the game
+///     Requires more than 39 bullets, and always costs 45 miles of travel
+///     The shot (B1 in the BASIC code is its response) determines the outcome:
+///         Bullseye:           food += 52+6*RND(), ammo -= 10+4*RND()
+///         100*RND() < 13*B1:  missed, ammo -= 10+3*B1
+///         Otherwise:          food += 48-2*B1, ammo -= 10+3*B1
+pub fn hunt<W: Write, P: Prompter, G: Rng, C: Clock>(supplies: &mut Supplies, trip: &mut Trip, marksman: &MarksmanQuality,
+        out: &mut W, prompter: &mut P, rng: &mut G, clock: &mut C) -> Result<HuntResult, PromptError> {
+    if supplies.ammo_left() <= HUNT_MIN_AMMO {
+        out.write_all(HUNT_NEED_BULLETS.as_bytes()).unwrap();
+        return Ok(HuntResult::NotEnoughAmmo);
+    }
+    trip.reverse(HUNT_MILES);
+
+    let shot = shoot(out, prompter, rng, clock, marksman)?;
+    let response = shot.response();
+    let result = if let ShotOutcome::Bullseye(_) = shot {
+        supplies.add_food(52 + rng.gen_range(0..6));
+        supplies.use_ammo(10 + rng.gen_range(0..4));
+        out.write_all(HUNT_BIG_ONE.as_bytes()).unwrap();
+        HuntResult::BigOne
+    } else if rng.gen_range(0..100) < 13 * response {
+        supplies.use_ammo(10 + 3 * response);
+        out.write_all(HUNT_MISSED.as_bytes()).unwrap();
+        HuntResult::Missed
+    } else {
+        supplies.add_food(48 - 2 * response);
+        supplies.use_ammo(10 + 3 * response);
+        out.write_all(HUNT_NICE_SHOT.as_bytes()).unwrap();
+        HuntResult::NiceShot
+    };
+    out.flush().unwrap();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hunt, typing the correct word for the shot when `typed` is None
+    fn run_hunt(supplies: &mut Supplies, trip: &mut Trip, typed: Option<&str>, reaction: Duration) -> (HuntResult, String) {
+        let mut shot = ShotFixture::new(typed, reaction);
+        let result = hunt(supplies, trip, &MarksmanQuality::Ace, &mut shot.cout, &mut shot.prompter, &mut shot.rng, &mut shot.clock).unwrap();
+        (result, shot.output())
+    }
+
+    #[test]
+    fn test_hunt_not_enough_ammo() {
+        let mut supplies = outfitted(0, 39, 0, 0);
+        let mut trip = Trip::new();
+        trip.travel(200);
+        let (result, hunt_out) = run_hunt(&mut supplies, &mut trip, None, Duration::ZERO);
+
+        assert_eq!(HuntResult::NotEnoughAmmo, result);
+        assert_eq!(HUNT_NEED_BULLETS, hunt_out);
+        assert_eq!(39, supplies.ammo_left());
+        assert_eq!(200, trip.miles_traveled());
+    }
+
+    #[test]
+    fn test_hunt_costs_miles() {
+        let mut supplies = outfitted(0, 100, 0, 0);
+        let mut trip = Trip::new();
+        trip.travel(200);
+        run_hunt(&mut supplies, &mut trip, None, Duration::ZERO);
+        assert_eq!(155, trip.miles_traveled());
+    }
+
+    #[test]
+    fn test_hunt_bullseye() {
+        let mut supplies = outfitted(0, 100, 0, 0);
+        let mut trip = Trip::new();
+        trip.travel(200);
+        let (result, hunt_out) = run_hunt(&mut supplies, &mut trip, None, Duration::ZERO);
+
+        assert_eq!(HuntResult::BigOne, result);
+        assert!(hunt_out.ends_with(HUNT_BIG_ONE));
+        assert!(supplies.food_left() >= 52);
+        assert!(supplies.food_left() < 52 + 6);
+        assert!(supplies.ammo_left() <= 100 - 10);
+        assert!(supplies.ammo_left() > 100 - 10 - 4);
+    }
+
+    #[test]
+    fn test_hunt_nice_shot() {
+        let mut supplies = outfitted(0, 100, 0, 0);
+        let mut trip = Trip::new();
+        trip.travel(200);
+        let (result, hunt_out) = run_hunt(&mut supplies, &mut trip, None, Duration::from_millis(2500));
+
+        assert_eq!(HuntResult::NiceShot, result);
+        assert!(hunt_out.ends_with(HUNT_NICE_SHOT));
+        assert_eq!(48 - 2 * 2, supplies.food_left());
+        assert_eq!(100 - 10 - 3 * 2, supplies.ammo_left());
+    }
+
+    #[test]
+    fn test_hunt_misspelled_misses() {
+        let mut supplies = outfitted(0, 100, 0, 0);
+        let mut trip = Trip::new();
+        trip.travel(200);
+        let (result, hunt_out) = run_hunt(&mut supplies, &mut trip, Some("BNAG"), Duration::ZERO);
+
+        assert_eq!(HuntResult::Missed, result);
+        assert!(hunt_out.ends_with(HUNT_MISSED));
+        assert_eq!(0, supplies.food_left());
+        assert_eq!(100 - 10 - 3 * SHOT_MISFIRE_RESPONSE, supplies.ammo_left());
+    }
+}
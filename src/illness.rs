@@ -0,0 +1,282 @@
+use std::io::Write;
+use rand::Rng;
+use crate::eating::*;
+use crate::supplies::*;
+use crate::money::*;
+use crate::trip::*;
+use crate::finish::*;
+use crate::save::*;
+#[cfg(test)]
+use std::io::Cursor;
+#[cfg(test)]
+use crate::store::*;
+#[cfg(test)]
+use crate::testing::*;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand::rngs::StdRng;
+
+/// The cost of seeing the doctor when sick or injured
+pub const DOCTOR_BILL: Money = Money::from_dollars(20);
+/// Mild and bad illnesses slow down the trip
+pub const ILLNESS_MILES: u32 = 5;
+
+const ILLNESS_MILD: &str = "Mild illness---medicine used\n";
+const ILLNESS_BAD: &str = "Bad illness---medicine used\n";
+const ILLNESS_SERIOUS: &str = "Serious illness---you must stop for medical attention\n";
+const ILLNESS_NO_MEDICINE: &str = "You ran out of medical supplies\n";
+const DOCTOR_UNAFFORDABLE: &str = "You can't afford a doctor\n";
+macro_rules! DOCTOR_BILL_PAID { () => { "Doctor's bill is $\x1B[32m{}\x1B[0m\n" }; }
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Illness {
+    Mild,
+    Bad,
+    Serious,
+}
+
+impl Illness {
+    /// Roll the severity of an illness, the better the party eats the milder it will be (BASIC code):
+    ///     Mild if 100*RND() < 10+35*(E-1)
+    ///     Bad if 100*RND() < 100-(40/4^(E-1))
+    ///     Otherwise Serious
+    pub fn roll<G: Rng>(eating: &EatingQuality, rng: &mut G) -> Illness {
+        let (mild, bad) = match eating {
+            EatingQuality::Poorly => (10.0, 60.0),
+            EatingQuality::Moderately => (45.0, 90.0),
+            EatingQuality::Well => (80.0, 97.5),
+        };
+        if rng.gen_range(0.0..100.0) < mild { Illness::Mild }
+        else if rng.gen_range(0.0..100.0) < bad { Illness::Bad }
+        else { Illness::Serious }
+    }
+
+    /// Retrieve the medicine (miscellaneous supplies) used to treat the illness
+    pub fn medicine(&self) -> u32 {
+        match self {
+            Illness::Mild => 2,
+            Illness::Bad => 5,
+            Illness::Serious => 10,
+        }
+    }
+}
+
+/// The health of the party, whether someone is sick (S4) or injured (K8) and needs a doctor
+pub struct Health {
+    sick: bool,
+    injured: bool,
+}
+
+impl Default for Health {
+    fn default() -> Health {
+        Health::new()
+    }
+}
+
+impl Health {
+    /// Constructor
+    ///     The party sets out healthy
+    pub fn new() -> Health {
+        Health {
+            sick: false,
+            injured: false,
+        }
+    }
+
+    /// Save the health of the party
+    pub fn save(&self, file: &mut SaveFile) {
+        file.set("health.sick", self.sick);
+        file.set("health.injured", self.injured);
+    }
+
+    /// Load the health of the party, as saved
+    pub fn load(file: &SaveFile) -> Result<Health, SaveError> {
+        Ok(Health {
+            sick: file.get("health.sick")?,
+            injured: file.get("health.injured")?,
+        })
+    }
+
+    /// Is someone in the party seriously ill?
+    pub fn is_sick(&self) -> bool {
+        self.sick
+    }
+
+    /// Is someone in the party injured?
+    pub fn is_injured(&self) -> bool {
+        self.injured
+    }
+
+    /// Someone in the party was injured and must see the doctor
+    pub fn injure(&mut self) {
+        self.injured = true;
+    }
+
+    /// Roll for illness taking hold of the party when it is going around, those eating poorly fall ill most often
+    pub fn check_illness<W: Write, G: Rng>(&mut self, eating: &EatingQuality, supplies: &mut Supplies, trip: &mut Trip,
+            out: &mut W, rng: &mut G) -> Result<(), DeathCause> {
+        if !rng.gen_bool(eating.illness_chance()) {
+            return Ok(());
+        }
+        self.fall_ill(eating, supplies, trip, out, rng)
+    }
+
+    /// Someone in the party falls ill, using up medicine and slowing the trip
+    ///     A serious illness also requires seeing the doctor next turn
+    ///     Running out of medicine is fatal
+    pub fn fall_ill<W: Write, G: Rng>(&mut self, eating: &EatingQuality, supplies: &mut Supplies, trip: &mut Trip,
+            out: &mut W, rng: &mut G) -> Result<(), DeathCause> {
+        let illness = Illness::roll(eating, rng);
+        match illness {
+            Illness::Mild => {
+                out.write_all(ILLNESS_MILD.as_bytes()).unwrap();
+                trip.reverse(ILLNESS_MILES);
+            },
+            Illness::Bad => {
+                out.write_all(ILLNESS_BAD.as_bytes()).unwrap();
+                trip.reverse(ILLNESS_MILES);
+            },
+            Illness::Serious => {
+                out.write_all(ILLNESS_SERIOUS.as_bytes()).unwrap();
+                self.sick = true;
+            },
+        }
+
+        if !supplies.use_medicine(illness.medicine()) {
+            out.write_all(ILLNESS_NO_MEDICINE.as_bytes()).unwrap();
+            return Err(self.death_cause());
+        }
+        Ok(())
+    }
+
+    /// See the doctor if anyone is sick or injured, which is fatal if the bill can't be paid
+    pub fn see_doctor<W: Write>(&mut self, supplies: &mut Supplies, out: &mut W) -> Result<(), DeathCause> {
+        if !self.sick && !self.injured {
+            return Ok(());
+        }
+        if supplies.pay(DOCTOR_BILL).is_err() {
+            out.write_all(DOCTOR_UNAFFORDABLE.as_bytes()).unwrap();
+            return Err(self.death_cause());
+        }
+        out.write_all(format!(DOCTOR_BILL_PAID!(), DOCTOR_BILL).as_bytes()).unwrap();
+        self.sick = false;
+        self.injured = false;
+        Ok(())
+    }
+
+    /// The cause of death when medical care runs out
+    fn death_cause(&self) -> DeathCause {
+        if self.injured { DeathCause::Injuries } else { DeathCause::Pneumonia }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_illness_medicine() {
+        assert_eq!(2, Illness::Mild.medicine());
+        assert_eq!(5, Illness::Bad.medicine());
+        assert_eq!(10, Illness::Serious.medicine());
+    }
+
+    #[test]
+    fn test_illness_roll_eating_well_is_milder() {
+        let mut rng = StdRng::seed_from_u64(1847);
+        let poorly = (0..1000).filter(|_| Illness::roll(&EatingQuality::Poorly, &mut rng) == Illness::Mild).count();
+        let well = (0..1000).filter(|_| Illness::roll(&EatingQuality::Well, &mut rng) == Illness::Mild).count();
+        assert!(well > poorly);
+    }
+
+    #[test]
+    fn test_health_constructor() {
+        let health = Health::new();
+        assert!(!health.is_sick());
+        assert!(!health.is_injured());
+    }
+
+    #[test]
+    fn test_health_fall_ill() {
+        let mut health = Health::new();
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_misc(&mut store, Money::from_dollars(50)).unwrap();
+        let mut trip = Trip::new();
+        trip.travel(200);
+        let mut cout = Cursor::new(Vec::new());
+        let mut rng = StdRng::seed_from_u64(1847);
+
+        health.fall_ill(&EatingQuality::Moderately, &mut supplies, &mut trip, &mut cout, &mut rng).unwrap();
+        assert_eq!(ILLNESS_BAD, read_out(&mut cout));
+        assert!(!health.is_sick());
+        assert_eq!(45, supplies.misc_left());
+        assert_eq!(195, trip.miles_traveled());
+    }
+
+    #[test]
+    fn test_health_check_illness_eating_poorly() {
+        let mut health = Health::new();
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_misc(&mut store, Money::from_dollars(50)).unwrap();
+        let mut trip = Trip::new();
+        let mut cout = Cursor::new(Vec::new());
+        let mut rng = StdRng::seed_from_u64(1847);
+
+        health.check_illness(&EatingQuality::Poorly, &mut supplies, &mut trip, &mut cout, &mut rng).unwrap();
+        assert!(supplies.misc_left() < 50);
+    }
+
+    #[test]
+    fn test_health_fall_ill_no_medicine() {
+        let mut health = Health::new();
+        let mut supplies = Supplies::new();
+        let mut trip = Trip::new();
+        let mut cout = Cursor::new(Vec::new());
+        let mut rng = StdRng::seed_from_u64(1847);
+
+        let cause = health.fall_ill(&EatingQuality::Poorly, &mut supplies, &mut trip, &mut cout, &mut rng).unwrap_err();
+        assert_eq!(DeathCause::Pneumonia, cause);
+        assert!(read_out(&mut cout).ends_with(ILLNESS_NO_MEDICINE));
+    }
+
+    #[test]
+    fn test_health_see_doctor_healthy() {
+        let mut health = Health::new();
+        let mut supplies = Supplies::new();
+        let mut cout = Cursor::new(Vec::new());
+        health.see_doctor(&mut supplies, &mut cout).unwrap();
+        assert_eq!(Money::from_dollars(700), supplies.money_left());
+        assert_eq!("", read_out(&mut cout));
+    }
+
+    #[test]
+    fn test_health_see_doctor_injured() {
+        let mut health = Health::new();
+        health.injure();
+        let mut supplies = Supplies::new();
+        let mut cout = Cursor::new(Vec::new());
+        health.see_doctor(&mut supplies, &mut cout).unwrap();
+        assert_eq!(Money::from_dollars(680), supplies.money_left());
+        assert!(!health.is_injured());
+        assert_eq!(format!(DOCTOR_BILL_PAID!(), DOCTOR_BILL), read_out(&mut cout));
+    }
+
+    #[test]
+    fn test_health_see_doctor_unaffordable() {
+        let mut health = Health::new();
+        health.injure();
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_food(&mut store, Money::from_dollars(690)).unwrap();
+        let mut cout = Cursor::new(Vec::new());
+        let cause = health.see_doctor(&mut supplies, &mut cout).unwrap_err();
+        assert_eq!(DeathCause::Injuries, cause);
+        assert_eq!(Money::from_dollars(10), supplies.money_left());
+        assert_eq!(DOCTOR_UNAFFORDABLE, read_out(&mut cout));
+    }
+}
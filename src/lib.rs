@@ -0,0 +1,27 @@
+//! The 1978 Oregon Trail, as a library
+//!     The `game` engine plays the trip turn by turn, reading and writing through whatever streams it is given,
+//!     so the game can be driven by a terminal, tests, bots or any other frontend
+
+pub mod ask;
+pub mod prompt;
+pub mod banner;
+pub mod strings;
+pub mod game;
+pub mod marksman;
+pub mod eating;
+pub mod illness;
+pub mod riders;
+pub mod events;
+pub mod mountains;
+pub mod weather;
+pub mod shooting;
+pub mod clock;
+pub mod supplies;
+pub mod store;
+pub mod money;
+pub mod trip;
+pub mod save;
+pub mod finish;
+pub mod hunt;
+#[cfg(test)]
+mod testing;
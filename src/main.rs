@@ -1,81 +1,144 @@
-use std::io::*;
-use crate::banner::*;
-use crate::ask::*;
-use crate::supplies::*;
-use crate::trip::*;
-use crate::finish::*;
+use std::io::{stdin, stdout, Write, BufReader};
+use std::fs::File;
+use std::path::Path;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use rust_oregontrail_1978::banner::*;
+use rust_oregontrail_1978::ask::*;
+use rust_oregontrail_1978::prompt::*;
+use rust_oregontrail_1978::supplies::*;
+use rust_oregontrail_1978::clock::*;
+use rust_oregontrail_1978::store::*;
+use rust_oregontrail_1978::money::*;
+use rust_oregontrail_1978::finish::*;
+use rust_oregontrail_1978::game::*;
+use rust_oregontrail_1978::save::*;
+use crate::options::*;
 
-mod banner;
-mod ask;
-mod marksman;
-mod supplies;
-mod trip;
-mod finish;
+mod options;
 
-const ASK_OXEN_SPEND: &str = "How much do you want to spend on your \x1B[31mOxen team\x1B[0m? ";
+macro_rules! GAME_SAVED { () => { "\nGame saved to {}, resume it with --load {}\n" }; }
+macro_rules! ASK_REPLACE_SAVE { () => { "There's already a game saved to {}, do you want to replace it (y/n)? " }; }
 
 fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        },
+    };
     let mut stdout = stdout();
-    let stdin = stdin();
-    print_banner(&mut stdout);
+    let mut prompter = TerminalPrompter::new(stdin().lock());
+    let game = match &options.load {
+        Some(path) => match load_game(path) {
+            Ok(game) => game,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            },
+        },
+        None => {
+            // Every random event in the game comes from this one generator, so a seed replays the same game
+            let rng = match options.seed {
+                Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+                None => ChaCha12Rng::from_entropy(),
+            };
+            match outfit(rng, &mut stdout, &mut prompter) {
+                Ok(game) => game,
+                Err(PromptError::EndOfInput) => {
+                    stdout.write_all(ABANDONED_IN_INDEPENDENCE.as_bytes()).unwrap();
+                    std::process::exit(1);
+                },
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                },
+            }
+        },
+    };
 
-    let _marksman = ask_marksman(&mut stdout, &mut stdin.lock());
+    let save_path = options.load.as_deref().unwrap_or(DEFAULT_SAVE_FILE);
+    match play(game, save_path, &mut stdout, &mut prompter) {
+        Ok(GameStatus::Quit) => std::process::exit(1),
+        Ok(_) => {},
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        },
+    }
+}
 
-    let mut supplies = Supplies::new();
-    ask_ok!(supplies.buy_oxen(ask!(ASK_OXEN_SPEND, &mut stdout, &mut stdin.lock())));
-    supplies.buy(&mut stdout, &mut stdin.lock());
-    supplies.set_premium(0.333);
+/// Outfit a new game at Independence, choosing a marksman and buying supplies
+fn outfit<W: Write, P: Prompter>(rng: ChaCha12Rng, out: &mut W, prompter: &mut P) -> Result<Game<ChaCha12Rng, SystemClock>, PromptError> {
+    print_banner(out);
 
-    let mut trip = Trip::new();
-    let mut fort_available = false;
-    loop {
-        println!("\n=================================================================");
-        if trip.miles_traveled() >= 2040 {
-            complete_trip(&mut stdout, &mut supplies);
-            std::process::exit(0);
-        }
+    let marksman = ask_marksman(out, prompter)?;
 
-        if supplies.food_left() <= 12 {
-            println!("You'd better do some hunting or buy some food, and soon!!!!");
-        }
-        println!("Total mileage traveled: {}\nIt is now {}\nSupplies remaining:\n{}", 
-            trip.miles_traveled(), trip.current_date().format("%A %d-%b-%Y"), supplies);
+    let mut supplies = Supplies::new();
+    prompter.number_accepted(ASK_OXEN_SPEND, OXEN_MIN_SPEND.dollars()..=OXEN_MAX_SPEND.dollars(), out,
+        |spend| supplies.buy_oxen(Money::from_dollars(spend)))?;
+    supplies.buy(&mut Store::independence(), out, prompter)?;
 
-        // Prompt for an action
-        let action;
-        if fort_available { action = ask_fort_hunt_continue(&mut stdout, &mut stdin.lock()) }
-        else { action = ask_hunt_continue(&mut stdout, &mut stdin.lock()); }
-        match action {
-            TurnAction::Fort => {
-                supplies.buy(&mut stdout, &mut stdin.lock());
-                fort_available = false;
-                trip.reverse(45);
-            },
-            TurnAction::Hunt => {
-                hunt(&mut supplies, &mut stdout, &mut stdin.lock())
-            },
-            TurnAction::Continue => {
-                fort_available = true
-            },
-        }
+    Ok(Game::new(marksman, supplies, rng, SystemClock::new()))
+}
 
-        // After turn actions are complete, see if we can survive
-        if supplies.food_left() <= 14 {
-            println!("\n=================================================================");
-            println!("You ran out of food and starved to death.");
-            handle_death(&mut stdout, &mut stdin.lock());
-            std::process::exit(0);
-        }
+/// Load a game saved part way along the trail
+fn load_game(path: &str) -> Result<Game<ChaCha12Rng, SystemClock>, SaveError> {
+    let file = File::open(path)?;
+    Game::load(BufReader::new(file), SystemClock::new())
+}
 
-        // Determine if a fort will be available
-        if supplies.ammo_left() > 39 { fort_available = true; }
+/// Save a game part way along the trail, replacing any game already saved there
+fn save_game(game: &Game<ChaCha12Rng, SystemClock>, path: &str) -> Result<(), SaveError> {
+    let mut file = File::create(path)?;
+    game.save(&mut file)
+}
 
-        // Travel along the Oregon Trail
-        trip.turn(supplies.oxen_left());
+/// Play a game along the trail through to the end, returning how it ended
+///     Running out of input quits the game, saving it leaves the game in progress
+fn play<W: Write, P: Prompter>(mut game: Game<ChaCha12Rng, SystemClock>, save_path: &str, out: &mut W, prompter: &mut P) -> Result<GameStatus, PromptError> {
+    while game.status() == GameStatus::InProgress {
+        out.write_all(b"\n=================================================================\n")?;
+        game.report(out);
+
+        // Prompt for an action (until one can actually be taken)
+        loop {
+            let action = if game.fort_available() { ask_fort_hunt_continue(out, prompter) }
+                else { ask_hunt_continue(out, prompter) };
+            match action {
+                Ok(TurnAction::SaveAndQuit) => {
+                    // A game already saved there is only replaced if the player says so
+                    if Path::new(save_path).exists() {
+                        match prompter.yes_no(&format!(ASK_REPLACE_SAVE!(), save_path), out) {
+                            Ok(true) => {},
+                            Ok(false) => continue,
+                            Err(PromptError::EndOfInput) => {
+                                game.quit();
+                                break;
+                            },
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    match save_game(&game, save_path) {
+                        Ok(()) => {
+                            write!(out, GAME_SAVED!(), save_path, save_path)?;
+                            return Ok(game.status());
+                        },
+                        Err(e) => writeln!(out, "{}", e)?,
+                    }
+                },
+                Ok(action) => if game.step(action, out, prompter)? != TurnOutcome::Unavailable { break; },
+                Err(PromptError::EndOfInput) => {
+                    game.quit();
+                    break;
+                },
+                Err(e) => return Err(e),
+            }
+        }
     }
 
+    out.write_all(b"\n=================================================================\n")?;
+    game.finish(out, prompter)?;
+    Ok(game.status())
 }
-
-fn hunt<W: Write, R: BufRead>(supplies: &mut Supplies, out: &mut W, input: &mut R) {
-
-}
\ No newline at end of file
@@ -1,6 +1,7 @@
 
 #[derive(PartialEq)]
 #[derive(Debug)]
+#[derive(Clone, Copy)]
 pub enum MarksmanQuality {
     Unknown,
     Ace,
@@ -21,6 +22,18 @@ impl MarksmanQuality {
             _ => MarksmanQuality::Unknown
         }
     }
+
+    /// Retrieve the marksman rating (D9 in the BASIC code), 1 (Ace) through 5 (Shaky)
+    ///     An Unknown marksman is given the benefit of the doubt and rated as Shaky
+    pub fn rating(&self) -> u32 {
+        match self {
+            MarksmanQuality::Ace => 1,
+            MarksmanQuality::GoodShot => 2,
+            MarksmanQuality::Fair => 3,
+            MarksmanQuality::NeedPractice => 4,
+            MarksmanQuality::Shaky | MarksmanQuality::Unknown => 5,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -52,6 +65,18 @@ mod tests {
         assert_eq!(MarksmanQuality::Shaky, MarksmanQuality::from_u32(5));
     }
 
+    #[test]
+    fn rating_round_trip() {
+        for value in 1..=5 {
+            assert_eq!(value, MarksmanQuality::from_u32(value).rating());
+        }
+    }
+
+    #[test]
+    fn rating_unknown() {
+        assert_eq!(5, MarksmanQuality::Unknown.rating());
+    }
+
     #[test]
     fn u32_unknown() {
         assert_eq!(MarksmanQuality::Unknown, MarksmanQuality::from_u32(0));
@@ -0,0 +1,127 @@
+use std::fmt;
+
+/// An amount of money, kept in cents so prices like $4.50 are exact
+///     Arithmetic is checked (or saturating where running out is expected), nothing silently wraps
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Money(u32);
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+impl Money {
+    /// No money at all
+    pub const ZERO: Money = Money(0);
+
+    /// The most whole dollars an amount can hold
+    pub const MAX_DOLLARS: u32 = u32::MAX / 100;
+
+    /// An amount in whole dollars, anything over `MAX_DOLLARS` is capped at the most an amount can hold
+    pub const fn from_dollars(dollars: u32) -> Money {
+        Money(dollars.saturating_mul(100))
+    }
+
+    /// An amount in cents
+    pub const fn from_cents(cents: u32) -> Money {
+        Money(cents)
+    }
+
+    /// Retrieve the amount in cents
+    pub fn cents(&self) -> u32 {
+        self.0
+    }
+
+    /// Retrieve the whole dollars in the amount, any cents are dropped
+    pub fn dollars(&self) -> u32 {
+        self.0 / 100
+    }
+
+    /// Add two amounts, None if the total is too large to keep
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        self.0.checked_add(other.0).map(Money)
+    }
+
+    /// Take one amount from another, None if there isn't enough
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        self.0.checked_sub(other.0).map(Money)
+    }
+
+    /// Take one amount from another, leaving nothing if there isn't enough
+    pub fn saturating_sub(self, other: Money) -> Money {
+        Money(self.0.saturating_sub(other.0))
+    }
+
+    /// Multiply an amount (e.g. a unit price) by a count, None if the total is too large to keep
+    pub fn checked_mul(self, count: u32) -> Option<Money> {
+        self.0.checked_mul(count).map(Money)
+    }
+
+    /// Retrieve how many whole units at the given price the amount pays for
+    ///     Any change left over is not counted, a free unit pays for nothing
+    pub fn whole_units(self, price: Money) -> u32 {
+        self.0.checked_div(price.0).unwrap_or(0)
+    }
+
+    /// Retrieve a fraction (numerator / denominator) of the amount
+    ///     Rounded to the nearest cent, half a cent rounds up, and capped at the most an amount can hold
+    pub fn fraction(self, numerator: u32, denominator: u32) -> Money {
+        let cents = (self.0 as u64 * numerator as u64 * 2 + denominator as u64) / (denominator as u64 * 2);
+        Money(cents.min(u32::MAX as u64) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_money_display() {
+        assert_eq!("4.50", format!("{}", Money::from_cents(450)));
+        assert_eq!("700.00", format!("{}", Money::from_dollars(700)));
+        assert_eq!("0.07", format!("{}", Money::from_cents(7)));
+    }
+
+    #[test]
+    fn test_money_dollars() {
+        assert_eq!(4, Money::from_cents(450).dollars());
+        assert_eq!(Money::from_cents(2000), Money::from_dollars(20));
+        assert_eq!(Money::MAX_DOLLARS, Money::from_dollars(Money::MAX_DOLLARS).dollars());
+        assert_eq!(Money::from_cents(u32::MAX), Money::from_dollars(Money::MAX_DOLLARS + 1));
+    }
+
+    #[test]
+    fn test_money_checked_arithmetic() {
+        let money = Money::from_dollars(5);
+        assert_eq!(Some(Money::from_cents(950)), money.checked_add(Money::from_cents(450)));
+        assert_eq!(None, money.checked_add(Money::from_cents(u32::MAX)));
+        assert_eq!(Some(Money::from_cents(50)), money.checked_sub(Money::from_cents(450)));
+        assert_eq!(None, money.checked_sub(Money::from_cents(501)));
+        assert_eq!(Some(Money::from_dollars(15)), money.checked_mul(3));
+        assert_eq!(None, money.checked_mul(u32::MAX));
+        assert_eq!(Money::ZERO, money.saturating_sub(Money::from_dollars(6)));
+    }
+
+    #[test]
+    fn test_money_whole_units() {
+        assert_eq!(133, Money::from_dollars(200).whole_units(Money::from_cents(150)));
+        assert_eq!(0, Money::from_dollars(200).whole_units(Money::ZERO));
+    }
+
+    #[test]
+    fn test_money_fraction() {
+        assert_eq!(Money::from_cents(467), Money::from_dollars(7).fraction(2, 3));
+        assert_eq!(Money::from_cents(1), Money::from_cents(1).fraction(1, 2));
+        assert_eq!(Money::from_cents(0), Money::from_cents(1).fraction(1, 3));
+    }
+
+    #[test]
+    fn test_money_fraction_capped() {
+        assert_eq!(Money::from_cents(u32::MAX), Money::from_cents(u32::MAX).fraction(1, 1));
+        assert_eq!(Money::from_cents(u32::MAX), Money::from_cents(u32::MAX / 2 + 1).fraction(2, 1));
+        assert_eq!(Money::from_cents(u32::MAX), Money::from_cents(u32::MAX).fraction(3, 2));
+    }
+}
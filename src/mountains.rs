@@ -0,0 +1,257 @@
+use std::io::Write;
+use rand::Rng;
+use crate::eating::*;
+use crate::finish::*;
+use crate::illness::*;
+use crate::supplies::*;
+use crate::trip::*;
+use crate::events::*;
+use crate::save::*;
+#[cfg(test)]
+use std::io::Cursor;
+#[cfg(test)]
+use crate::testing::*;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand::rngs::StdRng;
+
+/// Past this many miles the trail enters the mountains, starting with South Pass
+pub const MOUNTAINS_MILES: u32 = 950;
+/// Past this many miles the trail crosses the Blue Mountains
+pub const BLUE_MOUNTAINS_MILES: u32 = 1700;
+
+const MOUNTAINS_RUGGED: &str = "Rugged mountains\n";
+const MOUNTAINS_LOST: &str = "You got lost---spend valuable time trying to find trail!\n";
+const MOUNTAINS_WAGON_DAMAGED: &str = "Wagon damaged!---lose time and supplies\n";
+const MOUNTAINS_SLOW: &str = "The going gets slow\n";
+const MOUNTAINS_SOUTH_PASS: &str = "You made it safely through South Pass--no snow\n";
+const MOUNTAINS_BLIZZARD: &str = "Blizzard in mountain pass--time and supplies lost\n";
+
+/// Retrieve the chance (0.0 - 1.0) of the mountains being rugged, calculated as (BASIC code):
+///     (9-((M/100-15)^2+72)/((M/100-15)^2+12))/10
+///     The mountains are least likely to be rugged around 1500 miles (30%)
+pub fn rugged_chance(miles: u32) -> f64 {
+    let distance = (miles as f64 / 100.0 - 15.0).powi(2);
+    (9.0 - (distance + 72.0) / (distance + 12.0)) / 10.0
+}
+
+/// The mountain passes along the trail, and whether they've been crossed yet
+pub struct Mountains {
+    south_pass: bool,
+    blue_mountains: bool,
+}
+
+impl Default for Mountains {
+    fn default() -> Mountains {
+        Mountains::new()
+    }
+}
+
+impl Mountains {
+    /// Constructor
+    ///     No mountain passes have been crossed
+    pub fn new() -> Mountains {
+        Mountains {
+            south_pass: false,
+            blue_mountains: false,
+        }
+    }
+
+    /// Save which mountain passes have been crossed
+    pub fn save(&self, file: &mut SaveFile) {
+        file.set("mountains.south_pass", self.south_pass);
+        file.set("mountains.blue_mountains", self.blue_mountains);
+    }
+
+    /// Load which mountain passes have been crossed, as saved
+    pub fn load(file: &SaveFile) -> Result<Mountains, SaveError> {
+        Ok(Mountains {
+            south_pass: file.get("mountains.south_pass")?,
+            blue_mountains: file.get("mountains.blue_mountains")?,
+        })
+    }
+
+    /// Has the party made it through South Pass?
+    pub fn south_pass_cleared(&self) -> bool {
+        self.south_pass
+    }
+
+    /// Has the party made it through the Blue Mountains?
+    pub fn blue_mountains_cleared(&self) -> bool {
+        self.blue_mountains
+    }
+
+    /// Cross the mountains (once past 950 miles)
+    ///     Rugged mountains may get you lost, damage the wagon, or just slow you down
+    ///     South Pass has an 80% chance of a blizzard, and the Blue Mountains (past 1700 miles) 70%
+    pub fn cross<W: Write, G: Rng>(&mut self, supplies: &mut Supplies, trip: &mut Trip, health: &mut Health,
+            eating: &EatingQuality, out: &mut W, rng: &mut G) -> Result<(), DeathCause> {
+        let miles = trip.miles_traveled();
+        if miles <= MOUNTAINS_MILES {
+            return Ok(());
+        }
+
+        if rng.gen_bool(rugged_chance(miles)) {
+            out.write_all(MOUNTAINS_RUGGED.as_bytes()).unwrap();
+            if rng.gen_bool(0.1) {
+                out.write_all(MOUNTAINS_LOST.as_bytes()).unwrap();
+                trip.reverse(60);
+            } else if rng.gen_bool(0.11) {
+                out.write_all(MOUNTAINS_WAGON_DAMAGED.as_bytes()).unwrap();
+                repair_wagon(5, supplies, trip, out);
+                supplies.use_ammo(200);
+                trip.reverse(20 + rng.gen_range(0..30));
+            } else {
+                out.write_all(MOUNTAINS_SLOW.as_bytes()).unwrap();
+                trip.reverse(45 + rng.gen_range(0..50));
+            }
+        }
+
+        if !self.south_pass {
+            self.south_pass = true;
+            if rng.gen_bool(0.8) {
+                return blizzard(supplies, trip, health, eating, out, rng);
+            }
+            out.write_all(MOUNTAINS_SOUTH_PASS.as_bytes()).unwrap();
+        } else if miles >= BLUE_MOUNTAINS_MILES && !self.blue_mountains {
+            self.blue_mountains = true;
+            if rng.gen_bool(0.7) {
+                return blizzard(supplies, trip, health, eating, out, rng);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Caught in a blizzard in the mountain pass, losing time and supplies:
+///     food -= 25, misc -= 10, ammo -= 300, miles -= 30+40*RND()
+///     Without enough clothing (less than 18+2*RND()) the party also falls ill,
+///     and running out of medicine means freezing to death
+fn blizzard<W: Write, G: Rng>(supplies: &mut Supplies, trip: &mut Trip, health: &mut Health,
+        eating: &EatingQuality, out: &mut W, rng: &mut G) -> Result<(), DeathCause> {
+    out.write_all(MOUNTAINS_BLIZZARD.as_bytes()).unwrap();
+    supplies.lose_food(25);
+    supplies.lose_misc(10);
+    supplies.use_ammo(300);
+    trip.reverse(30 + rng.gen_range(0..40));
+    if supplies.clothes_left() < 18 + rng.gen_range(0..2) {
+        return health.fall_ill(eating, supplies, trip, out, rng).map_err(|_| DeathCause::Blizzard);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rugged_chance() {
+        assert!((rugged_chance(1500) - 0.3).abs() < 1e-9);
+        assert!(rugged_chance(1000) > rugged_chance(1500));
+        assert!(rugged_chance(2040) > rugged_chance(1500));
+    }
+
+    #[test]
+    fn test_mountains_constructor() {
+        let mountains = Mountains::new();
+        assert!(!mountains.south_pass_cleared());
+        assert!(!mountains.blue_mountains_cleared());
+    }
+
+    #[test]
+    fn test_mountains_cross_plains() {
+        let mut mountains = Mountains::new();
+        let mut supplies = outfitted(50, 300, 50, 50);
+        let mut trip = Trip::new();
+        trip.travel(MOUNTAINS_MILES);
+        let mut health = Health::new();
+        let mut cout = Cursor::new(Vec::new());
+        let mut rng = StdRng::seed_from_u64(1847);
+
+        mountains.cross(&mut supplies, &mut trip, &mut health, &EatingQuality::Well, &mut cout, &mut rng).unwrap();
+        assert!(!mountains.south_pass_cleared());
+        assert_eq!(MOUNTAINS_MILES, trip.miles_traveled());
+        assert_eq!("", read_out(&mut cout));
+    }
+
+    #[test]
+    fn test_mountains_cross_south_pass() {
+        let mut mountains = Mountains::new();
+        let mut supplies = outfitted(50, 300, 50, 50);
+        let mut trip = Trip::new();
+        trip.travel(1000);
+        let mut health = Health::new();
+        let mut cout = Cursor::new(Vec::new());
+        let mut rng = StdRng::seed_from_u64(1847);
+
+        mountains.cross(&mut supplies, &mut trip, &mut health, &EatingQuality::Well, &mut cout, &mut rng).unwrap();
+        assert!(mountains.south_pass_cleared());
+        assert!(!mountains.blue_mountains_cleared());
+        // This seed clears South Pass without running into rugged mountains or a blizzard
+        assert_eq!(MOUNTAINS_SOUTH_PASS, read_out(&mut cout));
+        assert_eq!(1000, trip.miles_traveled());
+    }
+
+    #[test]
+    fn test_mountains_cross_blue_mountains() {
+        let mut mountains = Mountains::new();
+        let mut supplies = outfitted(50, 300, 50, 50);
+        let mut trip = Trip::new();
+        trip.travel(1000);
+        let mut health = Health::new();
+        let mut cout = Cursor::new(Vec::new());
+        let mut rng = StdRng::seed_from_u64(1847);
+
+        mountains.cross(&mut supplies, &mut trip, &mut health, &EatingQuality::Well, &mut cout, &mut rng).unwrap();
+        trip.travel(800);
+        mountains.cross(&mut supplies, &mut trip, &mut health, &EatingQuality::Well, &mut cout, &mut rng).unwrap();
+        assert!(mountains.south_pass_cleared());
+        assert!(mountains.blue_mountains_cleared());
+    }
+
+    #[test]
+    fn test_blizzard_warm() {
+        let mut supplies = outfitted(50, 300, 50, 50);
+        let mut trip = Trip::new();
+        trip.travel(1000);
+        let mut health = Health::new();
+        let mut cout = Cursor::new(Vec::new());
+        let mut rng = StdRng::seed_from_u64(1847);
+
+        blizzard(&mut supplies, &mut trip, &mut health, &EatingQuality::Well, &mut cout, &mut rng).unwrap();
+        assert_eq!(MOUNTAINS_BLIZZARD, read_out(&mut cout));
+        assert_eq!(25, supplies.food_left());
+        assert_eq!(40, supplies.misc_left());
+        assert_eq!(0, supplies.ammo_left());
+        assert!(trip.miles_traveled() <= 970);
+        assert!(trip.miles_traveled() > 930);
+    }
+
+    #[test]
+    fn test_blizzard_no_clothes() {
+        let mut supplies = outfitted(50, 300, 0, 50);
+        let mut trip = Trip::new();
+        trip.travel(1000);
+        let mut health = Health::new();
+        let mut cout = Cursor::new(Vec::new());
+        let mut rng = StdRng::seed_from_u64(1847);
+
+        blizzard(&mut supplies, &mut trip, &mut health, &EatingQuality::Well, &mut cout, &mut rng).unwrap();
+        assert!(read_out(&mut cout).len() > MOUNTAINS_BLIZZARD.len());
+        assert!(supplies.misc_left() < 40);
+    }
+
+    #[test]
+    fn test_blizzard_freeze() {
+        let mut supplies = Supplies::new();
+        let mut trip = Trip::new();
+        trip.travel(1000);
+        let mut health = Health::new();
+        let mut cout = Cursor::new(Vec::new());
+        let mut rng = StdRng::seed_from_u64(1847);
+
+        let cause = blizzard(&mut supplies, &mut trip, &mut health, &EatingQuality::Well, &mut cout, &mut rng).unwrap_err();
+        assert_eq!(DeathCause::Blizzard, cause);
+    }
+}
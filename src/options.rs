@@ -0,0 +1,111 @@
+use std::fmt;
+use std::error::Error;
+
+const USAGE: &str = "Usage: rust_oregontrail_1978 [--seed <number>] [--load <file>]";
+/// Where a new game is saved, a loaded game is saved back to the file it was loaded from
+pub const DEFAULT_SAVE_FILE: &str = "oregon_trail.sav";
+
+/// The command line options for a game
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub struct Options {
+    /// Seed for the random number generator, the same seed (and the same answers) replays the same game
+    pub seed: Option<u64>,
+    /// A saved game to resume instead of starting a new one
+    pub load: Option<String>,
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub struct OptionsError {
+    message: String,
+}
+
+impl Error for OptionsError {}
+
+impl fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\n{}", self.message, USAGE)
+    }
+}
+
+impl Options {
+    /// Parse the command line arguments (not including the program name)
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Options, OptionsError> {
+        let mut options = Options { seed: None, load: None };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or_else(|| OptionsError { message: "--seed needs a number".to_string() })?;
+                    let seed = value.parse::<u64>()
+                        .map_err(|_| OptionsError { message: format!("--seed needs a number, not '{}'", value) })?;
+                    options.seed = Some(seed);
+                },
+                "--load" => {
+                    let file = args.next().ok_or_else(|| OptionsError { message: "--load needs a file".to_string() })?;
+                    options.load = Some(file);
+                },
+                _ => return Err(OptionsError { message: format!("Unknown option '{}'", arg) }),
+            }
+        }
+        // A saved game carries on with its own random numbers
+        if options.seed.is_some() && options.load.is_some() {
+            return Err(OptionsError { message: "--seed can't be used with --load".to_string() });
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, OptionsError> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_options_none() {
+        assert_eq!(Ok(Options { seed: None, load: None }), parse(&[]));
+    }
+
+    #[test]
+    fn test_options_seed() {
+        assert_eq!(Ok(Options { seed: Some(1847), load: None }), parse(&["--seed", "1847"]));
+    }
+
+    #[test]
+    fn test_options_seed_missing() {
+        let error = parse(&["--seed"]).unwrap_err();
+        assert_eq!(format!("--seed needs a number\n{}", USAGE), format!("{}", error));
+    }
+
+    #[test]
+    fn test_options_seed_not_a_number() {
+        let error = parse(&["--seed", "oregon"]).unwrap_err();
+        assert_eq!(format!("--seed needs a number, not 'oregon'\n{}", USAGE), format!("{}", error));
+    }
+
+    #[test]
+    fn test_options_load() {
+        assert_eq!(Ok(Options { seed: None, load: Some("trail.sav".to_string()) }), parse(&["--load", "trail.sav"]));
+    }
+
+    #[test]
+    fn test_options_load_missing() {
+        let error = parse(&["--load"]).unwrap_err();
+        assert_eq!(format!("--load needs a file\n{}", USAGE), format!("{}", error));
+    }
+
+    #[test]
+    fn test_options_seed_and_load() {
+        let error = parse(&["--seed", "1847", "--load", "trail.sav"]).unwrap_err();
+        assert_eq!(format!("--seed can't be used with --load\n{}", USAGE), format!("{}", error));
+    }
+
+    #[test]
+    fn test_options_unknown() {
+        let error = parse(&["--fast"]).unwrap_err();
+        assert_eq!(format!("Unknown option '--fast'\n{}", USAGE), format!("{}", error));
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::VecDeque;
+use std::io::{self, Write, BufRead};
+use std::num::IntErrorKind;
+use std::ops::RangeInclusive;
+use std::fmt;
+use std::error::Error;
+#[cfg(test)]
+use std::io::{Cursor, Seek, SeekFrom};
+
+/// The reason a prompt couldn't be answered
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum PromptError {
+    /// The input ended (e.g. Ctrl-D, or a closed pipe) before the question was answered
+    EndOfInput,
+    /// Asking the question or reading the answer failed
+    Io(io::ErrorKind),
+}
+
+impl Error for PromptError {}
+
+impl fmt::Display for PromptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PromptError::EndOfInput => write!(f, "The input ended before the question was answered"),
+            PromptError::Io(kind) => write!(f, "Unable to read an answer: {}", kind),
+        }
+    }
+}
+
+impl From<io::Error> for PromptError {
+    fn from(error: io::Error) -> PromptError {
+        PromptError::Io(error.kind())
+    }
+}
+
+/// The reason an answer isn't an acceptable number
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum NumberError {
+    /// Nothing was entered
+    Blank,
+    /// The answer isn't a number at all
+    NotANumber(String),
+    /// The number is below zero
+    Negative,
+    /// The number has a fraction (e.g. 12.50)
+    Decimal,
+    /// A whole number, but outside the bounds (inclusive) the question allows
+    OutOfRange(u32, u32),
+}
+
+impl Error for NumberError {}
+
+impl fmt::Display for NumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NumberError::Blank => write!(f, "\tPlease enter a number"),
+            NumberError::NotANumber(answer) => write!(f, "\t'{}' isn't a number, please enter a number", answer),
+            NumberError::Negative => write!(f, "\tThe number can't be negative"),
+            NumberError::Decimal => write!(f, "\tPlease enter a whole number, without a fraction"),
+            NumberError::OutOfRange(min, max) if min == max => write!(f, "\tPlease enter {}", min),
+            NumberError::OutOfRange(min, max) => write!(f, "\tPlease enter a number from {} to {}", min, max),
+        }
+    }
+}
+
+/// Read an answer as a whole number within the bounds (inclusive) a question allows
+///     Whitespace is ignored, so "1 000" is read as 1000
+pub fn parse_number(answer: &str, bounds: &RangeInclusive<u32>) -> Result<u32, NumberError> {
+    let answer: String = answer.chars().filter(|c| !c.is_whitespace()).collect();
+    if answer.is_empty() {
+        return Err(NumberError::Blank);
+    }
+    let out_of_range = NumberError::OutOfRange(*bounds.start(), *bounds.end());
+    match answer.parse::<u32>() {
+        Ok(number) if bounds.contains(&number) => Ok(number),
+        Ok(_) => Err(out_of_range),
+        Err(e) if *e.kind() == IntErrorKind::PosOverflow => Err(out_of_range),
+        Err(_) => match answer.parse::<f64>() {
+            Ok(number) if number.is_finite() && number.is_sign_negative() => Err(NumberError::Negative),
+            Ok(number) if number.is_finite() && answer.contains('.') => Err(NumberError::Decimal),
+            _ => Err(NumberError::NotANumber(answer)),
+        },
+    }
+}
+
+/// A way of asking the player questions
+///     Questions are written to the given output, only how the answers are found differs
+pub trait Prompter {
+    /// Ask a question answered with a line of free text, the line ending is not included in the answer
+    fn text<W: Write>(&mut self, question: &str, out: &mut W) -> Result<String, PromptError>;
+
+    /// Ask a question answered with a whole number within the bounds (inclusive) given
+    ///     Anything else is explained, and the question asked again
+    fn number<W: Write>(&mut self, question: &str, bounds: RangeInclusive<u32>, out: &mut W) -> Result<u32, PromptError> {
+        loop {
+            match parse_number(&self.text(question, out)?, &bounds) {
+                Ok(number) => return Ok(number),
+                Err(e) => writeln!(out, "{}", e)?,
+            }
+        }
+    }
+
+    /// Ask a Yes/No question, anything other than No is taken as Yes
+    fn yes_no<W: Write>(&mut self, question: &str, out: &mut W) -> Result<bool, PromptError> {
+        let answer = self.text(question, out)?;
+        Ok(!answer.trim_start().starts_with('n'))
+    }
+
+    /// Ask for a choice from a menu numbered 1 through `choices`
+    fn menu<W: Write>(&mut self, question: &str, choices: u32, out: &mut W) -> Result<u32, PromptError> {
+        self.number(question, 1..=choices, out)
+    }
+
+    /// Ask for a number within the bounds given until `accept` takes it, explaining why any other answer was refused
+    fn number_accepted<W, T, E, F>(&mut self, question: &str, bounds: RangeInclusive<u32>, out: &mut W, mut accept: F)
+            -> Result<T, PromptError> where W: Write, E: fmt::Display, F: FnMut(u32) -> Result<T, E> {
+        loop {
+            match accept(self.number(question, bounds.clone(), out)?) {
+                Ok(accepted) => return Ok(accepted),
+                Err(e) => writeln!(out, "{}", e)?,
+            }
+        }
+    }
+}
+
+/// Answers typed in at the terminal, or read from any other input stream
+pub struct TerminalPrompter<R: BufRead> {
+    input: R,
+}
+
+impl<R: BufRead> TerminalPrompter<R> {
+    pub fn new(input: R) -> TerminalPrompter<R> {
+        TerminalPrompter { input }
+    }
+}
+
+impl<R: BufRead> Prompter for TerminalPrompter<R> {
+    fn text<W: Write>(&mut self, question: &str, out: &mut W) -> Result<String, PromptError> {
+        out.write_all(question.as_bytes())?;
+        out.flush()?;
+
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer)? == 0 {
+            return Err(PromptError::EndOfInput);
+        }
+        let line = answer.trim_end_matches(['\r', '\n']).len();
+        answer.truncate(line);
+        Ok(answer)
+    }
+}
+
+/// Answers that follow a script, so prompts can be tested without an input stream
+///     Each question takes the next scripted answer,
+///     once the script runs out the input has ended
+pub struct ScriptedPrompter {
+    answers: VecDeque<String>,
+}
+
+impl ScriptedPrompter {
+    pub fn new(answers: &[&str]) -> ScriptedPrompter {
+        ScriptedPrompter {
+            answers: answers.iter().map(|answer| answer.to_string()).collect(),
+        }
+    }
+}
+
+impl Prompter for ScriptedPrompter {
+    fn text<W: Write>(&mut self, question: &str, out: &mut W) -> Result<String, PromptError> {
+        out.write_all(question.as_bytes())?;
+        out.flush()?;
+        self.answers.pop_front().ok_or(PromptError::EndOfInput)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    fn terminal(input: &str) -> TerminalPrompter<Cursor<Vec<u8>>> {
+        let mut cin = Cursor::new(Vec::new());
+        cin.write_all(input.as_bytes()).unwrap();
+        cin.seek(SeekFrom::Start(0)).unwrap();
+        TerminalPrompter::new(cin)
+    }
+
+    #[test]
+    fn test_parse_number() {
+        let bounds = 0..=1000;
+        assert_eq!(Ok(12), parse_number("12", &bounds));
+        assert_eq!(Ok(0), parse_number(" 0\r\n", &bounds));
+        assert_eq!(Ok(1000), parse_number("1 000", &bounds));
+        assert_eq!(Err(NumberError::Blank), parse_number(" ", &bounds));
+        assert_eq!(Err(NumberError::NotANumber("abc".to_string())), parse_number("abc", &bounds));
+        assert_eq!(Err(NumberError::NotANumber("NaN".to_string())), parse_number("NaN", &bounds));
+        assert_eq!(Err(NumberError::Negative), parse_number("-5", &bounds));
+        assert_eq!(Err(NumberError::Negative), parse_number("-0.5", &bounds));
+        assert_eq!(Err(NumberError::Decimal), parse_number("12.50", &bounds));
+        assert_eq!(Err(NumberError::OutOfRange(0, 1000)), parse_number("1001", &bounds));
+        assert_eq!(Err(NumberError::OutOfRange(0, 1000)), parse_number("99999999999", &bounds));
+        assert_eq!(Err(NumberError::OutOfRange(200, 300)), parse_number("100", &(200..=300)));
+    }
+
+    #[test]
+    fn test_number_error_display() {
+        assert_eq!("\tPlease enter a number", format!("{}", NumberError::Blank));
+        assert_eq!("\t'abc' isn't a number, please enter a number", format!("{}", NumberError::NotANumber("abc".to_string())));
+        assert_eq!("\tThe number can't be negative", format!("{}", NumberError::Negative));
+        assert_eq!("\tPlease enter a whole number, without a fraction", format!("{}", NumberError::Decimal));
+        assert_eq!("\tPlease enter a number from 200 to 300", format!("{}", NumberError::OutOfRange(200, 300)));
+        assert_eq!("\tPlease enter 1", format!("{}", NumberError::OutOfRange(1, 1)));
+    }
+
+    #[test]
+    fn test_terminal_number() {
+        let mut cout = Cursor::new(Vec::new());
+        assert_eq!(Ok(1), terminal("1").number("test? ", 0..=999, &mut cout));
+        assert_eq!(Ok(200), terminal("200").number("test? ", 0..=999, &mut cout));
+        assert_eq!(Ok(999), terminal("999\r\n").number("test? ", 0..=999, &mut cout));
+        assert_eq!(Ok(5), terminal("abc\n5\n").number("test? ", 0..=999, &mut cout));
+    }
+
+    #[test]
+    fn test_number_reprompts() {
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = ScriptedPrompter::new(&["", "abc", "-5", "12.50", "400", "250"]);
+        assert_eq!(Ok(250), prompter.number("oxen? ", 200..=300, &mut cout));
+        let errors = [NumberError::Blank, NumberError::NotANumber("abc".to_string()), NumberError::Negative,
+            NumberError::Decimal, NumberError::OutOfRange(200, 300)];
+        let expected: String = errors.iter().map(|e| format!("oxen? {}\n", e)).collect::<String>() + "oxen? ";
+        assert_eq!(expected, String::from_utf8(cout.into_inner()).unwrap());
+    }
+
+    #[test]
+    fn test_terminal_asks_question() {
+        let mut cout = Cursor::new(Vec::new());
+        terminal("1\n").number("test? ", 0..=1, &mut cout).unwrap();
+        let prompt_out = read_out(&mut cout);
+        assert_eq!("test? ", prompt_out);
+    }
+
+    #[test]
+    fn test_terminal_text() {
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = terminal(" BANG \r\nPOW\n");
+        assert_eq!(Ok(" BANG ".to_string()), prompter.text("test? ", &mut cout));
+        assert_eq!(Ok("POW".to_string()), prompter.text("test? ", &mut cout));
+    }
+
+    #[test]
+    fn test_terminal_end_of_input() {
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = terminal("\n");
+        assert_eq!(Ok(String::new()), prompter.text("test? ", &mut cout));
+        assert_eq!(Err(PromptError::EndOfInput), prompter.text("test? ", &mut cout));
+        assert_eq!(Err(PromptError::EndOfInput), terminal("").menu("test? ", 3, &mut cout));
+    }
+
+    #[test]
+    fn test_terminal_yes_no() {
+        let mut cout = Cursor::new(Vec::new());
+        assert_eq!(Ok(true), terminal("y").yes_no("test? ", &mut cout));
+        assert_eq!(Ok(false), terminal("n").yes_no("test? ", &mut cout));
+        assert_eq!(Ok(false), terminal(" no\r\n").yes_no("test? ", &mut cout));
+        assert_eq!(Ok(true), terminal("test").yes_no("test? ", &mut cout));
+        assert_eq!(Ok(true), terminal(" ").yes_no("test? ", &mut cout));
+    }
+
+    #[test]
+    fn test_scripted_prompter() {
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = ScriptedPrompter::new(&["4", "n", "WHAM"]);
+        assert_eq!(Ok(4), prompter.number("number? ", 0..=4, &mut cout));
+        assert_eq!(Ok(false), prompter.yes_no("yes? ", &mut cout));
+        assert_eq!(Ok("WHAM".to_string()), prompter.text("text? ", &mut cout));
+        assert_eq!(Err(PromptError::EndOfInput), prompter.text("text? ", &mut cout));
+        assert_eq!(b"number? yes? text? text? ".to_vec(), cout.into_inner());
+    }
+
+    #[test]
+    fn test_menu() {
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = ScriptedPrompter::new(&["0", "4", "3"]);
+        assert_eq!(Ok(3), prompter.menu("menu? ", 3, &mut cout));
+        let refused = format!("menu? {}\n", NumberError::OutOfRange(1, 3));
+        assert_eq!(format!("{}{}menu? ", refused, refused), String::from_utf8(cout.into_inner()).unwrap());
+    }
+
+    #[test]
+    fn test_number_accepted() {
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = ScriptedPrompter::new(&["5", "50"]);
+        let accepted = prompter.number_accepted("spend? ", 0..=100, &mut cout, |n| if n >= 10 { Ok(n) } else { Err("too little") });
+        assert_eq!(Ok(50), accepted);
+        assert_eq!(b"spend? too little\nspend? ".to_vec(), cout.into_inner());
+    }
+
+    #[test]
+    fn test_prompt_error_display() {
+        let error = PromptError::from(io::Error::from(io::ErrorKind::BrokenPipe));
+        assert_eq!(PromptError::Io(io::ErrorKind::BrokenPipe), error);
+        assert_eq!(format!("Unable to read an answer: {}", io::ErrorKind::BrokenPipe), format!("{}", error));
+    }
+}
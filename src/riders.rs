@@ -0,0 +1,257 @@
+use std::io::Write;
+use rand::Rng;
+use crate::ask::*;
+use crate::clock::*;
+use crate::finish::*;
+use crate::illness::*;
+use crate::marksman::*;
+use crate::prompt::*;
+use crate::shooting::*;
+use crate::supplies::*;
+use crate::trip::*;
+#[cfg(test)]
+use std::io::Cursor;
+#[cfg(test)]
+use std::time::Duration;
+#[cfg(test)]
+use crate::testing::*;
+
+const RIDERS_HOSTILE: &str = "Riders ahead. They look hostile\n";
+const RIDERS_NOT_HOSTILE: &str = "Riders ahead. They don't look hostile\n";
+const RIDERS_DID_NOT_ATTACK: &str = "They did not attack\n";
+const RIDERS_DROVE_OFF: &str = "Nice shooting---you drove them off\n";
+const RIDERS_SLOW: &str = "Kinda slow with your Colt .45\n";
+const RIDERS_KNIFED: &str = "Lousy shooting---you got knifed\nYou have to see ol' Doc Blanchard\n";
+const RIDERS_WERE_HOSTILE: &str = "Riders were hostile--check for losses\n";
+const RIDERS_WERE_FRIENDLY: &str = "Riders were friendly, but check for possible losses\n";
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum RiderTactic {
+    Unknown,
+    Run,
+    Attack,
+    Continue,
+    CircleWagons,
+}
+
+impl RiderTactic {
+    pub fn from_u32(value: u32) -> RiderTactic {
+        match value {
+            1 => RiderTactic::Run,
+            2 => RiderTactic::Attack,
+            3 => RiderTactic::Continue,
+            4 => RiderTactic::CircleWagons,
+            _ => RiderTactic::Unknown
+        }
+    }
+}
+
+/// Retrieve the chance (0.0 - 1.0) of riders appearing, calculated as (BASIC code):
+///     (((M/100-4)^2+72)/((M/100-4)^2+12)-1)/10
+///     Riders are most likely around 400 miles into the trip (50%), and become rare further west
+pub fn riders_chance(miles: u32) -> f64 {
+    let distance = (miles as f64 / 100.0 - 4.0).powi(2);
+    ((distance + 72.0) / (distance + 12.0) - 1.0) / 10.0
+}
+
+/// Riders ahead! Choose tactics to deal with them, although they may not be what they seem
+///     Riders look hostile 80% of the time, and 20% of the time their looks are deceiving
+///     Running out of bullets against hostile riders is fatal
+#[allow(clippy::too_many_arguments)]
+pub fn riders<W: Write, P: Prompter, G: Rng, C: Clock>(supplies: &mut Supplies, trip: &mut Trip, health: &mut Health,
+        marksman: &MarksmanQuality, out: &mut W, prompter: &mut P, rng: &mut G, clock: &mut C) -> Result<(), Interruption> {
+    let mut hostile = rng.gen_bool(0.8);
+    out.write_all(if hostile { RIDERS_HOSTILE } else { RIDERS_NOT_HOSTILE }.as_bytes()).unwrap();
+    let tactic = ask_tactics(out, prompter)?;
+    if rng.gen_bool(0.2) {
+        hostile = !hostile;
+    }
+
+    if hostile {
+        hostile_riders(tactic, supplies, trip, health, marksman, out, prompter, rng, clock)
+    } else {
+        friendly_riders(tactic, supplies, trip, out);
+        Ok(())
+    }
+}
+
+/// Deal with hostile riders:
+///     Run:            miles += 20, misc -= 15, ammo -= 150, oxen -= 40
+///     Attack:         shoot, ammo -= 40*B1+80
+///     Continue:       20% they don't attack, otherwise misc -= 15, ammo -= 150
+///     Circle Wagons:  shoot, ammo -= 30*B1+80, miles -= 25
+#[allow(clippy::too_many_arguments)]
+fn hostile_riders<W: Write, P: Prompter, G: Rng, C: Clock>(tactic: RiderTactic, supplies: &mut Supplies, trip: &mut Trip,
+        health: &mut Health, marksman: &MarksmanQuality, out: &mut W, prompter: &mut P, rng: &mut G, clock: &mut C) -> Result<(), Interruption> {
+    let enough_ammo = match tactic {
+        RiderTactic::Run => {
+            trip.travel(20);
+            supplies.lose_misc(15);
+            supplies.lose_oxen(40);
+            supplies.use_ammo(150)
+        },
+        RiderTactic::Continue if rng.gen_bool(0.2) => {
+            out.write_all(RIDERS_DID_NOT_ATTACK.as_bytes()).unwrap();
+            true
+        },
+        RiderTactic::Continue => {
+            supplies.lose_misc(15);
+            supplies.use_ammo(150)
+        },
+        RiderTactic::Attack | RiderTactic::CircleWagons | RiderTactic::Unknown => {
+            let shot = shoot(out, prompter, rng, clock, marksman)?;
+            let enough_ammo = if tactic == RiderTactic::CircleWagons {
+                trip.reverse(25);
+                supplies.use_ammo(30 * shot.response() + 80)
+            } else {
+                supplies.use_ammo(40 * shot.response() + 80)
+            };
+            match shot {
+                ShotOutcome::Bullseye(_) => out.write_all(RIDERS_DROVE_OFF.as_bytes()).unwrap(),
+                ShotOutcome::Hit(_) => out.write_all(RIDERS_SLOW.as_bytes()).unwrap(),
+                ShotOutcome::Slow(_) | ShotOutcome::Misfire => {
+                    out.write_all(RIDERS_KNIFED.as_bytes()).unwrap();
+                    health.injure();
+                },
+            }
+            enough_ammo
+        },
+    };
+
+    out.write_all(RIDERS_WERE_HOSTILE.as_bytes()).unwrap();
+    if !enough_ammo {
+        return Err(Interruption::Died(DeathCause::Riders));
+    }
+    Ok(())
+}
+
+/// Deal with friendly riders, which can still cost you:
+///     Run:            miles += 15, oxen -= 10
+///     Attack:         miles -= 5, ammo -= 100
+///     Continue:       no losses
+///     Circle Wagons:  miles -= 20
+fn friendly_riders<W: Write>(tactic: RiderTactic, supplies: &mut Supplies, trip: &mut Trip, out: &mut W) {
+    match tactic {
+        RiderTactic::Run => {
+            trip.travel(15);
+            supplies.lose_oxen(10);
+        },
+        RiderTactic::Attack => {
+            trip.reverse(5);
+            supplies.use_ammo(100);
+        },
+        RiderTactic::Continue | RiderTactic::Unknown => {},
+        RiderTactic::CircleWagons => {
+            trip.reverse(20);
+        },
+    }
+    out.write_all(RIDERS_WERE_FRIENDLY.as_bytes()).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_hostile(tactic: RiderTactic, supplies: &mut Supplies, trip: &mut Trip, health: &mut Health,
+            typed: Option<&str>, reaction: Duration) -> (Result<(), Interruption>, String) {
+        let mut shot = ShotFixture::new(typed, reaction);
+        let result = hostile_riders(tactic, supplies, trip, health, &MarksmanQuality::Ace,
+            &mut shot.cout, &mut shot.prompter, &mut shot.rng, &mut shot.clock);
+        (result, shot.output())
+    }
+
+    #[test]
+    fn test_rider_tactic_from_u32() {
+        assert_eq!(RiderTactic::Run, RiderTactic::from_u32(1));
+        assert_eq!(RiderTactic::Attack, RiderTactic::from_u32(2));
+        assert_eq!(RiderTactic::Continue, RiderTactic::from_u32(3));
+        assert_eq!(RiderTactic::CircleWagons, RiderTactic::from_u32(4));
+        assert_eq!(RiderTactic::Unknown, RiderTactic::from_u32(5));
+    }
+
+    #[test]
+    fn test_riders_chance() {
+        assert!((riders_chance(400) - 0.5).abs() < 1e-9);
+        assert!(riders_chance(0) < riders_chance(400));
+        assert!(riders_chance(2000) < riders_chance(0));
+        assert!(riders_chance(2040) > 0.0);
+    }
+
+    #[test]
+    fn test_hostile_riders_run() {
+        let mut supplies = outfitted(0, 300, 0, 50);
+        let mut trip = Trip::new();
+        let mut health = Health::new();
+        let (result, riders_out) = run_hostile(RiderTactic::Run, &mut supplies, &mut trip, &mut health, None, Duration::ZERO);
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(RIDERS_WERE_HOSTILE, riders_out);
+        assert_eq!(20, trip.miles_traveled());
+        assert_eq!(150, supplies.ammo_left());
+        assert_eq!(35, supplies.misc_left());
+        assert_eq!(210, supplies.oxen_left());
+    }
+
+    #[test]
+    fn test_hostile_riders_attack_bullseye() {
+        let mut supplies = outfitted(0, 300, 0, 50);
+        let mut trip = Trip::new();
+        let mut health = Health::new();
+        let (result, riders_out) = run_hostile(RiderTactic::Attack, &mut supplies, &mut trip, &mut health, None, Duration::ZERO);
+
+        assert_eq!(Ok(()), result);
+        assert!(riders_out.ends_with(&format!("{}{}", RIDERS_DROVE_OFF, RIDERS_WERE_HOSTILE)));
+        assert_eq!(300 - 80, supplies.ammo_left());
+        assert!(!health.is_injured());
+    }
+
+    #[test]
+    fn test_hostile_riders_circle_wagons_slow() {
+        let mut supplies = outfitted(0, 300, 0, 50);
+        let mut trip = Trip::new();
+        trip.travel(100);
+        let mut health = Health::new();
+        let (result, riders_out) = run_hostile(RiderTactic::CircleWagons, &mut supplies, &mut trip, &mut health,
+            None, Duration::from_secs(3));
+
+        assert_eq!(Ok(()), result);
+        assert!(riders_out.ends_with(&format!("{}{}", RIDERS_SLOW, RIDERS_WERE_HOSTILE)));
+        assert_eq!(300 - 30 * 3 - 80, supplies.ammo_left());
+        assert_eq!(75, trip.miles_traveled());
+    }
+
+    #[test]
+    fn test_hostile_riders_knifed() {
+        let mut supplies = outfitted(0, 300, 0, 50);
+        let mut trip = Trip::new();
+        let mut health = Health::new();
+        let (result, riders_out) = run_hostile(RiderTactic::Attack, &mut supplies, &mut trip, &mut health,
+            Some("BOOM"), Duration::ZERO);
+
+        assert_eq!(Err(Interruption::Died(DeathCause::Riders)), result);
+        assert!(riders_out.contains(RIDERS_KNIFED));
+        assert!(health.is_injured());
+        assert_eq!(0, supplies.ammo_left());
+    }
+
+    #[test]
+    fn test_friendly_riders() {
+        let mut supplies = outfitted(0, 300, 0, 50);
+        let mut trip = Trip::new();
+        trip.travel(100);
+        let mut cout = Cursor::new(Vec::new());
+
+        friendly_riders(RiderTactic::Run, &mut supplies, &mut trip, &mut cout);
+        assert_eq!(115, trip.miles_traveled());
+        assert_eq!(240, supplies.oxen_left());
+        friendly_riders(RiderTactic::Attack, &mut supplies, &mut trip, &mut cout);
+        assert_eq!(110, trip.miles_traveled());
+        assert_eq!(200, supplies.ammo_left());
+        friendly_riders(RiderTactic::Continue, &mut supplies, &mut trip, &mut cout);
+        assert_eq!(110, trip.miles_traveled());
+        friendly_riders(RiderTactic::CircleWagons, &mut supplies, &mut trip, &mut cout);
+        assert_eq!(90, trip.miles_traveled());
+    }
+}
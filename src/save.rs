@@ -0,0 +1,164 @@
+use std::io::{self, Write, BufRead};
+use std::fmt;
+use std::error::Error;
+use std::str::FromStr;
+#[cfg(test)]
+use std::io::Cursor;
+
+/// The version of the save file format written, only files of this version can be loaded
+pub const SAVE_VERSION: u32 = 1;
+/// The first line of every save file, followed by its version
+const SAVE_HEADER: &str = "OREGON TRAIL SAVE";
+
+/// The reason a game couldn't be saved or loaded
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum SaveError {
+    /// Writing or reading the file failed
+    Io(io::ErrorKind),
+    /// The file isn't a saved game
+    NotASave,
+    /// The game was saved in another version of the format
+    Version(String),
+    /// A value the game needs isn't in the file
+    Missing(String),
+    /// A value in the file can't be read (key, value)
+    Invalid(String, String),
+}
+
+impl Error for SaveError {}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(kind) => write!(f, "Unable to read or write the saved game: {}", kind),
+            SaveError::NotASave => write!(f, "That isn't a saved game"),
+            SaveError::Version(version) =>
+                write!(f, "The game was saved in version {} of the format, only version {} can be loaded", version, SAVE_VERSION),
+            SaveError::Missing(key) => write!(f, "The saved game is missing '{}'", key),
+            SaveError::Invalid(key, value) => write!(f, "The saved game has an invalid '{}': '{}'", key, value),
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(error: io::Error) -> SaveError {
+        SaveError::Io(error.kind())
+    }
+}
+
+/// The state of a game as named values, one `key=value` per line after a versioned header
+///     Each part of the game saves and loads its own values, prefixed with its name (e.g. `trip.miles`)
+pub struct SaveFile {
+    values: Vec<(String, String)>,
+}
+
+impl Default for SaveFile {
+    fn default() -> SaveFile {
+        SaveFile::new()
+    }
+}
+
+impl SaveFile {
+    pub fn new() -> SaveFile {
+        SaveFile { values: Vec::new() }
+    }
+
+    /// Save a value, replacing any value already saved with the same key
+    pub fn set<V: fmt::Display>(&mut self, key: &str, value: V) {
+        let value = value.to_string();
+        match self.values.iter_mut().find(|(saved, _)| saved == key) {
+            Some((_, saved)) => *saved = value,
+            None => self.values.push((key.to_string(), value)),
+        }
+    }
+
+    /// Retrieve a saved value as text
+    pub fn text(&self, key: &str) -> Result<&str, SaveError> {
+        self.values.iter().find(|(saved, _)| saved == key).map(|(_, value)| value.as_str())
+            .ok_or_else(|| SaveError::Missing(key.to_string()))
+    }
+
+    /// Retrieve a saved value
+    pub fn get<V: FromStr>(&self, key: &str) -> Result<V, SaveError> {
+        let value = self.text(key)?;
+        value.parse::<V>().map_err(|_| SaveError::Invalid(key.to_string(), value.to_string()))
+    }
+
+    /// Write the file, header first
+    pub fn write<W: Write>(&self, out: &mut W) -> Result<(), SaveError> {
+        writeln!(out, "{} {}", SAVE_HEADER, SAVE_VERSION)?;
+        for (key, value) in self.values.iter() {
+            writeln!(out, "{}={}", key, value)?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Read a file written by `write`, checking it's a saved game of the current version
+    pub fn read<R: BufRead>(input: R) -> Result<SaveFile, SaveError> {
+        let mut lines = input.lines();
+        let header = lines.next().ok_or(SaveError::NotASave)??;
+        let version = header.strip_prefix(SAVE_HEADER).ok_or(SaveError::NotASave)?.trim();
+        if version != SAVE_VERSION.to_string() {
+            return Err(SaveError::Version(version.to_string()));
+        }
+
+        let mut file = SaveFile::new();
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| SaveError::Invalid(line.clone(), String::new()))?;
+            file.set(key, value);
+        }
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_file_round_trip() {
+        let mut file = SaveFile::new();
+        file.set("trip.miles", 640);
+        file.set("health.sick", true);
+        file.set("supplies.misc_history", "");
+        file.set("trip.miles", 700);
+
+        let mut saved = Vec::new();
+        file.write(&mut saved).unwrap();
+        assert_eq!("OREGON TRAIL SAVE 1\ntrip.miles=700\nhealth.sick=true\nsupplies.misc_history=\n", String::from_utf8(saved.clone()).unwrap());
+
+        let file = SaveFile::read(Cursor::new(saved)).unwrap();
+        assert_eq!(Ok(700), file.get::<u32>("trip.miles"));
+        assert_eq!(Ok(true), file.get::<bool>("health.sick"));
+        assert_eq!(Ok(""), file.text("supplies.misc_history"));
+    }
+
+    #[test]
+    fn test_save_file_missing_and_invalid() {
+        let file = SaveFile::read(Cursor::new("OREGON TRAIL SAVE 1\ntrip.miles=far\n")).unwrap();
+        assert_eq!(Err(SaveError::Missing("trip.date".to_string())), file.text("trip.date"));
+        assert_eq!(Err(SaveError::Invalid("trip.miles".to_string(), "far".to_string())), file.get::<u32>("trip.miles"));
+    }
+
+    #[test]
+    fn test_save_file_not_a_save() {
+        assert_eq!(Some(SaveError::NotASave), SaveFile::read(Cursor::new("")).err());
+        assert_eq!(Some(SaveError::NotASave), SaveFile::read(Cursor::new("10 PRINT \"OREGON\"\n")).err());
+        assert_eq!(Some(SaveError::Invalid("trip.miles".to_string(), String::new())),
+            SaveFile::read(Cursor::new("OREGON TRAIL SAVE 1\ntrip.miles\n")).err());
+    }
+
+    #[test]
+    fn test_save_file_version() {
+        let error = SaveFile::read(Cursor::new("OREGON TRAIL SAVE 2\n")).err().unwrap();
+        assert_eq!(SaveError::Version("2".to_string()), error);
+        assert_eq!("The game was saved in version 2 of the format, only version 1 can be loaded", format!("{}", error));
+    }
+}
@@ -0,0 +1,175 @@
+use std::io::Write;
+use rand::Rng;
+use crate::marksman::*;
+use crate::clock::*;
+use crate::prompt::*;
+#[cfg(test)]
+use crate::testing::*;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand::rngs::StdRng;
+#[cfg(test)]
+use std::time::Duration;
+
+/// The words that sound like a gun shot, one of which must be typed to fire
+pub const SHOT_WORDS: [&str; 4] = ["BANG", "BLAM", "POW", "WHAM"];
+/// The response given to a shot when the word was misspelled
+pub const SHOT_MISFIRE_RESPONSE: u32 = 9;
+
+macro_rules! SHOT_TYPE_WORD { () => { "Type \x1B[31m{}\x1B[0m: " }; }
+
+/// The graded outcome of a shot, carrying the response (B1 in the BASIC code) where there is one
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum ShotOutcome {
+    /// Right on target, a response of 1 or less
+    Bullseye(u32),
+    /// A decent shot, a response of 2 through 4
+    Hit(u32),
+    /// Kinda slow with your gun, a response over 4
+    Slow(u32),
+    /// The word was misspelled, the shot went wild
+    Misfire,
+}
+
+impl ShotOutcome {
+    /// Grade a shot from its response
+    pub fn from_response(response: u32) -> ShotOutcome {
+        match response {
+            0..=1 => ShotOutcome::Bullseye(response),
+            2..=4 => ShotOutcome::Hit(response),
+            _ => ShotOutcome::Slow(response),
+        }
+    }
+
+    /// Retrieve the response of the shot, 0 is perfect and the larger it is the worse the shot
+    pub fn response(&self) -> u32 {
+        match *self {
+            ShotOutcome::Bullseye(response) | ShotOutcome::Hit(response) | ShotOutcome::Slow(response) => response,
+            ShotOutcome::Misfire => SHOT_MISFIRE_RESPONSE,
+        }
+    }
+}
+
+/// Pick the word that must be typed to fire the gun
+pub fn shot_word<G: Rng>(rng: &mut G) -> &'static str {
+    SHOT_WORDS[rng.gen_range(0..SHOT_WORDS.len())]
+}
+
+/// Grade a shot given the word to type, what was typed, and how long it took (B1 in the BASIC code)
+///     The response is the seconds taken, less a head start for the self-proclaimed weaker shots:
+///         B1 = seconds - (D9 - 1), where D9 is the marksman rating (Ace = 1 through Shaky = 5)
+///     Any misspelling of the word is a misfire, regardless of how quickly it was typed
+pub fn grade_shot(word: &str, typed: &str, seconds: u32, marksman: &MarksmanQuality) -> ShotOutcome {
+    if !typed.trim().eq_ignore_ascii_case(word) {
+        return ShotOutcome::Misfire;
+    }
+    ShotOutcome::from_response(seconds.saturating_sub(marksman.rating() - 1))
+}
+
+/// Fire the trusty rifle, the player must type the word as fast as they can
+///     The clock times the response, only whole seconds count against the shot
+pub fn shoot<W: Write, P: Prompter, G: Rng, C: Clock>(out: &mut W, prompter: &mut P, rng: &mut G, clock: &mut C,
+        marksman: &MarksmanQuality) -> Result<ShotOutcome, PromptError> {
+    let word = shot_word(rng);
+    let question = format!(SHOT_TYPE_WORD!(), word);
+
+    let mut typed = Ok(String::new());
+    let reaction = timed(clock, || { typed = prompter.text(&question, out); });
+
+    Ok(grade_shot(word, &typed?, reaction.as_secs() as u32, marksman))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shot_outcome_from_response() {
+        assert_eq!(ShotOutcome::Bullseye(0), ShotOutcome::from_response(0));
+        assert_eq!(ShotOutcome::Bullseye(1), ShotOutcome::from_response(1));
+        assert_eq!(ShotOutcome::Hit(2), ShotOutcome::from_response(2));
+        assert_eq!(ShotOutcome::Hit(4), ShotOutcome::from_response(4));
+        assert_eq!(ShotOutcome::Slow(5), ShotOutcome::from_response(5));
+    }
+
+    #[test]
+    fn test_shot_outcome_response() {
+        assert_eq!(1, ShotOutcome::Bullseye(1).response());
+        assert_eq!(3, ShotOutcome::Hit(3).response());
+        assert_eq!(7, ShotOutcome::Slow(7).response());
+        assert_eq!(SHOT_MISFIRE_RESPONSE, ShotOutcome::Misfire.response());
+    }
+
+    #[test]
+    fn test_shot_word() {
+        let mut rng = StdRng::seed_from_u64(1847);
+        for _ in 0..20 {
+            assert!(SHOT_WORDS.contains(&shot_word(&mut rng)));
+        }
+    }
+
+    #[test]
+    fn test_grade_shot_misspelled() {
+        assert_eq!(ShotOutcome::Misfire, grade_shot("BANG", "BNAG", 0, &MarksmanQuality::Ace));
+        assert_eq!(ShotOutcome::Misfire, grade_shot("BANG", "", 0, &MarksmanQuality::Shaky));
+    }
+
+    #[test]
+    fn test_grade_shot_ignores_case_and_whitespace() {
+        assert_eq!(ShotOutcome::Bullseye(0), grade_shot("WHAM", " wham\r\n", 0, &MarksmanQuality::Ace));
+    }
+
+    #[test]
+    fn test_grade_shot_ace() {
+        assert_eq!(ShotOutcome::Bullseye(1), grade_shot("POW", "POW", 1, &MarksmanQuality::Ace));
+        assert_eq!(ShotOutcome::Hit(3), grade_shot("POW", "POW", 3, &MarksmanQuality::Ace));
+        assert_eq!(ShotOutcome::Slow(6), grade_shot("POW", "POW", 6, &MarksmanQuality::Ace));
+    }
+
+    #[test]
+    fn test_grade_shot_shaky() {
+        assert_eq!(ShotOutcome::Bullseye(0), grade_shot("BLAM", "BLAM", 3, &MarksmanQuality::Shaky));
+        assert_eq!(ShotOutcome::Bullseye(1), grade_shot("BLAM", "BLAM", 5, &MarksmanQuality::Shaky));
+        assert_eq!(ShotOutcome::Hit(2), grade_shot("BLAM", "BLAM", 6, &MarksmanQuality::Shaky));
+    }
+
+    fn run_shoot(typed: Option<&str>, reaction: Duration, marksman: &MarksmanQuality) -> ShotOutcome {
+        let mut shot = ShotFixture::new(typed, reaction);
+        let outcome = shoot(&mut shot.cout, &mut shot.prompter, &mut shot.rng, &mut shot.clock, marksman).unwrap();
+        assert_eq!(format!(SHOT_TYPE_WORD!(), shot.word), shot.output());
+        outcome
+    }
+
+    #[test]
+    fn test_shoot_instant() {
+        let outcome = run_shoot(None, Duration::ZERO, &MarksmanQuality::Ace);
+        assert_eq!(ShotOutcome::Bullseye(0), outcome);
+    }
+
+    #[test]
+    fn test_shoot_reaction_ace() {
+        let outcome = run_shoot(None, Duration::from_millis(1400), &MarksmanQuality::Ace);
+        assert_eq!(ShotOutcome::Bullseye(1), outcome);
+    }
+
+    #[test]
+    fn test_shoot_slow_ace() {
+        let outcome = run_shoot(None, Duration::from_millis(7900), &MarksmanQuality::Ace);
+        assert_eq!(ShotOutcome::Slow(7), outcome);
+    }
+
+    #[test]
+    fn test_shoot_slow_shaky() {
+        let outcome = run_shoot(None, Duration::from_millis(7900), &MarksmanQuality::Shaky);
+        assert_eq!(ShotOutcome::Hit(3), outcome);
+    }
+
+    #[test]
+    fn test_shoot_misspelled() {
+        let outcome = run_shoot(Some("KABOOM"), Duration::ZERO, &MarksmanQuality::Ace);
+        assert_eq!(ShotOutcome::Misfire, outcome);
+    }
+}
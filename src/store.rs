@@ -0,0 +1,408 @@
+use std::fmt;
+use crate::supplies::*;
+use crate::money::*;
+use crate::save::*;
+
+macro_rules! STORE_PRICE_SHEET { () => { "Prices at \x1B[1m{}\x1B[0m:\n" }; }
+macro_rules! STORE_PRICE_SHEET_ITEM { () => { "\t{}\t${} per {}\t({} in stock)\n" }; }
+macro_rules! STORE_CART { () => { "Your order at \x1B[1m{}\x1B[0m:\n" }; }
+macro_rules! STORE_CART_ITEM { () => { "\t{}\t${}\tbuys {}\n" }; }
+macro_rules! STORE_CART_TOTAL { () => { "\tTotal\t${}\tleaving you ${}\n" }; }
+macro_rules! STORE_CART_MARKUP { () => { "\t(${} more than the same goods cost in Independence)\n" }; }
+
+/// What a unit of any item costs in Independence, stores further west charge more
+pub const INDEPENDENCE_PRICE: Money = Money::from_dollars(1);
+
+/// A place to buy supplies, with its own prices and a limited stock of goods
+///     Prices are relative to Independence, where $1 buys one unit of any item
+pub struct Store {
+    name: &'static str,
+    miles: u32,
+    price: Money,
+    food: u32,
+    bullets: u32,
+    clothes: u32,
+    misc: u32,
+}
+
+impl fmt::Display for Store {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, STORE_PRICE_SHEET!(), self.name)?;
+        for item in SupplyItem::ALL {
+            write!(f, STORE_PRICE_SHEET_ITEM!(), item.name(), self.price, item.unit(), self.stock(item))?;
+        }
+        Ok(())
+    }
+}
+
+/// The purchases planned at a store, what's to be spent on each item
+///     Nothing changes hands until the whole cart is bought
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Cart {
+    food: Money,
+    bullets: Money,
+    clothes: Money,
+    misc: Money,
+}
+
+impl Default for Cart {
+    fn default() -> Cart {
+        Cart::new()
+    }
+}
+
+impl Cart {
+    pub fn new() -> Cart {
+        Cart { food: Money::ZERO, bullets: Money::ZERO, clothes: Money::ZERO, misc: Money::ZERO }
+    }
+
+    /// Retrieve what's to be spent on an item
+    pub fn spend(&self, item: SupplyItem) -> Money {
+        match item {
+            SupplyItem::Food => self.food,
+            SupplyItem::Bullets => self.bullets,
+            SupplyItem::Clothing => self.clothes,
+            SupplyItem::Misc => self.misc,
+        }
+    }
+
+    fn spend_mut(&mut self, item: SupplyItem) -> &mut Money {
+        match item {
+            SupplyItem::Food => &mut self.food,
+            SupplyItem::Bullets => &mut self.bullets,
+            SupplyItem::Clothing => &mut self.clothes,
+            SupplyItem::Misc => &mut self.misc,
+        }
+    }
+}
+
+impl Store {
+    /// Constructor
+    ///     `price` is what a unit costs here, compared to the $1 it costs in Independence
+    pub fn new(name: &'static str, miles: u32, price: Money, food: u32, bullets: u32, clothes: u32, misc: u32) -> Store {
+        Store { name, miles, price, food, bullets, clothes, misc }
+    }
+
+    /// The outfitters in Independence, Missouri, where the trip starts
+    ///     There's plenty of everything, at the regular price
+    pub fn independence() -> Store {
+        Store::new("Independence", 0, INDEPENDENCE_PRICE, 700, 700 * BULLETS_PER_DOLLAR, 700, 700)
+    }
+
+    /// The forts along the trail, going west prices climb and shelves empty
+    ///     Fort Kearney's prices match the BASIC code, where the fort only gives 2/3 of what is spent
+    pub fn forts() -> Vec<Store> {
+        vec![
+            Store::new("Fort Kearney", 300, Money::from_cents(150), 150, 5000, 50, 50),
+            Store::new("Fort Laramie", 640, Money::from_cents(160), 200, 5000, 60, 60),
+            Store::new("Fort Bridger", 1000, Money::from_cents(175), 100, 4000, 40, 40),
+            Store::new("Fort Hall", 1260, Money::from_cents(190), 100, 3000, 40, 30),
+            Store::new("Fort Boise", 1550, Money::from_cents(200), 80, 2500, 30, 30),
+            Store::new("Fort Walla Walla", 1800, Money::from_cents(225), 60, 2000, 20, 20),
+        ]
+    }
+
+    /// Save what the store has left in stock, under the given key
+    pub fn save(&self, file: &mut SaveFile, key: &str) {
+        file.set(key, format!("{} {} {} {}", self.food, self.bullets, self.clothes, self.misc));
+    }
+
+    /// Load what the store has left in stock, as saved under the given key
+    ///     The store's name, place and prices aren't saved, they come from the store itself
+    pub fn load(&mut self, file: &SaveFile, key: &str) -> Result<(), SaveError> {
+        let stock = file.text(key)?;
+        let counts = stock.split_whitespace().map(|count| count.parse::<u32>()).collect::<Result<Vec<u32>, _>>();
+        match counts.as_deref() {
+            Ok([food, bullets, clothes, misc]) => {
+                (self.food, self.bullets, self.clothes, self.misc) = (*food, *bullets, *clothes, *misc);
+                Ok(())
+            },
+            _ => Err(SaveError::Invalid(key.to_string(), stock.to_string())),
+        }
+    }
+
+    /// Retrieve the name of the store
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Retrieve how much of an item the store has left
+    pub fn stock(&self, item: SupplyItem) -> u32 {
+        match item {
+            SupplyItem::Food => self.food,
+            SupplyItem::Bullets => self.bullets,
+            SupplyItem::Clothing => self.clothes,
+            SupplyItem::Misc => self.misc,
+        }
+    }
+
+    fn stock_mut(&mut self, item: SupplyItem) -> &mut u32 {
+        match item {
+            SupplyItem::Food => &mut self.food,
+            SupplyItem::Bullets => &mut self.bullets,
+            SupplyItem::Clothing => &mut self.clothes,
+            SupplyItem::Misc => &mut self.misc,
+        }
+    }
+
+    /// Retrieve how much of an item a given amount of money buys here
+    ///     Only whole units are sold
+    pub fn quantity(&self, item: SupplyItem, spend: Money) -> u32 {
+        spend.whole_units(self.price) * item.per_unit()
+    }
+
+    /// Retrieve the most that can be spent on an item before the store runs out of it
+    ///     Anything short of the price of one more unit than is in stock
+    pub fn max_spend(&self, item: SupplyItem) -> Money {
+        let units = self.stock(item) / item.per_unit();
+        self.price.checked_mul(units + 1).map_or(Money::ZERO, |cost| cost.saturating_sub(Money::from_cents(1)))
+    }
+
+    /// Retrieve what spending a given amount on an item actually costs here
+    ///     Only the whole units bought are charged for, any change is handed back
+    pub fn cost(&self, item: SupplyItem, spend: Money) -> Money {
+        self.price.checked_mul(self.quantity(item, spend) / item.per_unit()).unwrap_or(spend)
+    }
+
+    /// Retrieve how much more an item costs here than the same goods would in Independence
+    pub fn markup(&self, item: SupplyItem, spend: Money) -> Money {
+        let units = self.quantity(item, spend) / item.per_unit();
+        self.cost(item, spend).saturating_sub(INDEPENDENCE_PRICE.checked_mul(units).unwrap_or(Money::ZERO))
+    }
+
+    /// Check the store has enough of an item in stock to sell the amount being spent on it
+    fn check_stock(&self, item: SupplyItem, spend: Money, available: Money) -> Result<(), BuyError> {
+        if self.quantity(item, spend) > self.stock(item) {
+            return Err(BuyError::out_of_stock(self.max_spend(item), spend, available));
+        }
+        Ok(())
+    }
+
+    /// Sell an item, taking the money from the supplies and handing over the goods
+    ///     Only the whole units bought are charged for, any change is handed back
+    pub fn sell(&mut self, supplies: &mut Supplies, item: SupplyItem, spend: Money) -> Result<(), BuyError> {
+        if spend > supplies.money_left() {
+            return Err(BuyError::insufficient_funds(spend, supplies.money_left()));
+        }
+        self.check_stock(item, spend, supplies.money_left())?;
+        let quantity = self.quantity(item, spend);
+        supplies.pay(self.cost(item, spend))?;
+        supplies.stock_up(item, quantity);
+        *self.stock_mut(item) -= quantity;
+        Ok(())
+    }
+
+    /// Retrieve what the whole cart costs here, None if it's too much to count
+    pub fn cart_total(&self, cart: &Cart) -> Option<Money> {
+        SupplyItem::ALL.iter().try_fold(Money::ZERO, |total, item| total.checked_add(self.cost(*item, cart.spend(*item))))
+    }
+
+    /// Put an item in the cart, replacing whatever was to be spent on it before
+    ///     The cart is left alone if the store can't sell that much, or the whole cart would cost more than is available
+    pub fn add_to_cart(&self, cart: &mut Cart, item: SupplyItem, spend: Money, available: Money) -> Result<(), BuyError> {
+        self.check_stock(item, spend, available)?;
+        let mut filled = *cart;
+        *filled.spend_mut(item) = spend;
+        self.check_cart(&filled, available)?;
+        *cart = filled;
+        Ok(())
+    }
+
+    /// Check the whole cart can be bought, every item in stock and enough money for all of it
+    fn check_cart(&self, cart: &Cart, available: Money) -> Result<(), BuyError> {
+        for item in SupplyItem::ALL {
+            self.check_stock(item, cart.spend(item), available)?;
+        }
+        match self.cart_total(cart) {
+            Some(total) if total <= available => Ok(()),
+            total => Err(BuyError::insufficient_funds(total.unwrap_or(available), available)),
+        }
+    }
+
+    /// Buy everything in the cart, all of it or (if any of it can't be bought) none of it
+    pub fn sell_cart(&mut self, supplies: &mut Supplies, cart: &Cart) -> Result<(), BuyError> {
+        self.check_cart(cart, supplies.money_left())?;
+        for item in SupplyItem::ALL {
+            // Only what the goods cost is handed over, the change never leaves the purse
+            let cost = self.cost(item, cart.spend(item));
+            self.sell(supplies, item, cost)?;
+        }
+        Ok(())
+    }
+
+    /// Sum up the cart: what each item costs and buys, the total and what would be left, and any markup over Independence
+    pub fn cart_summary(&self, cart: &Cart, available: Money) -> String {
+        let mut summary = format!(STORE_CART!(), self.name);
+        let mut markup = Money::ZERO;
+        for item in SupplyItem::ALL {
+            let spend = cart.spend(item);
+            summary += &format!(STORE_CART_ITEM!(), item.name(), self.cost(item, spend), self.quantity(item, spend));
+            markup = markup.checked_add(self.markup(item, spend)).unwrap_or(markup);
+        }
+        let total = self.cart_total(cart).unwrap_or(available);
+        summary += &format!(STORE_CART_TOTAL!(), total, available.saturating_sub(total));
+        if markup > Money::ZERO {
+            summary += &format!(STORE_CART_MARKUP!(), markup);
+        }
+        summary
+    }
+}
+
+/// Retrieve the last fort the party has reached on the trail, there is none until they reach the first fort
+pub fn nearest_fort(forts: &mut [Store], miles: u32) -> Option<&mut Store> {
+    forts.iter_mut().rev().find(|fort| fort.miles <= miles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_independence_quantity() {
+        let store = Store::independence();
+        assert_eq!(50, store.quantity(SupplyItem::Food, Money::from_dollars(50)));
+        assert_eq!(50 * BULLETS_PER_DOLLAR, store.quantity(SupplyItem::Bullets, Money::from_dollars(50)));
+    }
+
+    #[test]
+    fn test_store_fort_quantity() {
+        let store = Store::new("Fort Test", 0, Money::from_cents(150), 500, 500, 500, 500);
+        assert_eq!(133, store.quantity(SupplyItem::Clothing, Money::from_dollars(200)));
+        assert_eq!(6 * BULLETS_PER_DOLLAR, store.quantity(SupplyItem::Bullets, Money::from_dollars(10)));
+    }
+
+    #[test]
+    fn test_store_sell() {
+        let mut store = Store::new("Fort Test", 0, Money::from_cents(150), 150, 5000, 50, 50);
+        let mut supplies = Supplies::new();
+        store.sell(&mut supplies, SupplyItem::Food, Money::from_dollars(30)).unwrap();
+        assert_eq!(20, supplies.food_left());
+        assert_eq!(Money::from_dollars(670), supplies.money_left());
+        assert_eq!(130, store.stock(SupplyItem::Food));
+    }
+
+    #[test]
+    fn test_store_sell_out_of_stock() {
+        let mut store = Store::new("Fort Test", 0, Money::from_cents(150), 150, 5000, 50, 50);
+        let mut supplies = Supplies::new();
+        let error = store.sell(&mut supplies, SupplyItem::Food, Money::from_dollars(300)).unwrap_err();
+        assert_eq!(format!("{}", BuyError::out_of_stock(Money::from_cents(22649), Money::from_dollars(300), Money::from_dollars(700))), format!("{}", error));
+        assert_eq!(0, supplies.food_left());
+        assert_eq!(Money::from_dollars(700), supplies.money_left());
+        assert_eq!(150, store.stock(SupplyItem::Food));
+    }
+
+    #[test]
+    fn test_store_sell_insufficient_funds() {
+        let mut store = Store::independence();
+        let mut supplies = Supplies::new();
+        supplies.pay(Money::from_dollars(300)).unwrap();
+        let error = store.sell(&mut supplies, SupplyItem::Misc, Money::from_dollars(500)).unwrap_err();
+        assert!(format!("{}", error).starts_with("\tUnable to spend $500"));
+        assert_eq!(0, supplies.misc_left());
+        assert_eq!(700, store.stock(SupplyItem::Misc));
+    }
+
+    #[test]
+    fn test_store_max_spend() {
+        let mut store = Store::new("Fort Test", 0, Money::from_cents(150), 150, 5000, 50, 50);
+        let mut supplies = Supplies::new();
+        assert_eq!(Money::from_cents(22649), store.max_spend(SupplyItem::Food));
+        store.sell(&mut supplies, SupplyItem::Food, store.max_spend(SupplyItem::Food)).unwrap();
+        assert_eq!(0, store.stock(SupplyItem::Food));
+        assert_eq!(Money::from_dollars(700 - 225), supplies.money_left());
+    }
+
+    #[test]
+    fn test_store_price_sheet() {
+        let store = Store::new("Fort Test", 0, Money::from_cents(150), 1, 2, 3, 4);
+        let sheet = format!("{}", store);
+        assert!(sheet.starts_with(&format!(STORE_PRICE_SHEET!(), "Fort Test")));
+        assert!(sheet.contains(&format!(STORE_PRICE_SHEET_ITEM!(), SupplyItem::Bullets.name(), "1.50", "belt of 50", 2)));
+        assert_eq!(5, sheet.lines().count());
+    }
+
+    #[test]
+    fn test_nearest_fort() {
+        let mut forts = Store::forts();
+        assert!(nearest_fort(&mut forts, 0).is_none());
+        assert_eq!("Fort Kearney", nearest_fort(&mut forts, 300).unwrap().name());
+        assert_eq!("Fort Laramie", nearest_fort(&mut forts, 700).unwrap().name());
+        assert_eq!("Fort Laramie", nearest_fort(&mut forts, 999).unwrap().name());
+        assert_eq!("Fort Walla Walla", nearest_fort(&mut forts, 2000).unwrap().name());
+    }
+
+    #[test]
+    fn test_store_cost_and_markup() {
+        let store = Store::new("Fort Test", 0, Money::from_cents(150), 500, 500, 500, 500);
+        assert_eq!(Money::from_cents(1950), store.cost(SupplyItem::Food, Money::from_dollars(20)));
+        assert_eq!(Money::from_cents(650), store.markup(SupplyItem::Food, Money::from_dollars(20)));
+        assert_eq!(Money::ZERO, Store::independence().markup(SupplyItem::Bullets, Money::from_dollars(20)));
+    }
+
+    #[test]
+    fn test_store_add_to_cart() {
+        let store = Store::new("Fort Test", 0, Money::from_cents(150), 150, 5000, 50, 50);
+        let mut cart = Cart::new();
+        store.add_to_cart(&mut cart, SupplyItem::Food, Money::from_dollars(30), Money::from_dollars(100)).unwrap();
+        store.add_to_cart(&mut cart, SupplyItem::Misc, Money::from_dollars(60), Money::from_dollars(100)).unwrap();
+        assert_eq!(Some(Money::from_dollars(90)), store.cart_total(&cart));
+
+        // Changing an item replaces it, the cart is untouched if the change can't be made
+        store.add_to_cart(&mut cart, SupplyItem::Food, Money::from_dollars(15), Money::from_dollars(100)).unwrap();
+        let error = store.add_to_cart(&mut cart, SupplyItem::Food, Money::from_dollars(300), Money::from_dollars(100)).unwrap_err();
+        assert_eq!(format!("{}", BuyError::out_of_stock(Money::from_cents(22649), Money::from_dollars(300), Money::from_dollars(100))),
+            format!("{}", error));
+        let error = store.add_to_cart(&mut cart, SupplyItem::Bullets, Money::from_dollars(60), Money::from_dollars(100)).unwrap_err();
+        assert_eq!(format!("{}", BuyError::insufficient_funds(Money::from_dollars(135), Money::from_dollars(100))), format!("{}", error));
+        assert_eq!(Money::from_dollars(15), cart.spend(SupplyItem::Food));
+        assert_eq!(Money::ZERO, cart.spend(SupplyItem::Bullets));
+    }
+
+    #[test]
+    fn test_store_sell_cart() {
+        let mut store = Store::new("Fort Test", 0, Money::from_cents(150), 150, 5000, 50, 50);
+        let mut supplies = Supplies::new();
+        let mut cart = Cart::new();
+        store.add_to_cart(&mut cart, SupplyItem::Food, Money::from_dollars(31), supplies.money_left()).unwrap();
+        store.add_to_cart(&mut cart, SupplyItem::Bullets, Money::from_dollars(3), supplies.money_left()).unwrap();
+        store.sell_cart(&mut supplies, &cart).unwrap();
+        assert_eq!(20, supplies.food_left());
+        assert_eq!(2 * BULLETS_PER_DOLLAR, supplies.ammo_left());
+        assert_eq!(Money::from_dollars(700 - 33), supplies.money_left());
+        assert_eq!(130, store.stock(SupplyItem::Food));
+    }
+
+    #[test]
+    fn test_store_sell_cart_all_or_nothing() {
+        let mut store = Store::new("Fort Test", 0, Money::from_cents(150), 150, 5000, 50, 50);
+        let mut supplies = Supplies::new();
+        let mut cart = Cart::new();
+        store.add_to_cart(&mut cart, SupplyItem::Food, Money::from_dollars(30), supplies.money_left()).unwrap();
+        store.add_to_cart(&mut cart, SupplyItem::Misc, Money::from_dollars(60), supplies.money_left()).unwrap();
+        supplies.pay(Money::from_dollars(650)).unwrap();
+
+        assert!(store.sell_cart(&mut supplies, &cart).is_err());
+        assert_eq!(0, supplies.food_left());
+        assert_eq!(0, supplies.misc_left());
+        assert_eq!(Money::from_dollars(50), supplies.money_left());
+        assert_eq!(150, store.stock(SupplyItem::Food));
+    }
+
+    #[test]
+    fn test_store_cart_summary() {
+        let store = Store::new("Fort Test", 0, Money::from_cents(150), 150, 5000, 50, 50);
+        let mut cart = Cart::new();
+        store.add_to_cart(&mut cart, SupplyItem::Food, Money::from_dollars(30), Money::from_dollars(100)).unwrap();
+        let summary = store.cart_summary(&cart, Money::from_dollars(100));
+        assert!(summary.starts_with(&format!(STORE_CART!(), "Fort Test")));
+        assert!(summary.contains(&format!(STORE_CART_ITEM!(), SupplyItem::Food.name(), "30.00", 20)));
+        assert!(summary.contains(&format!(STORE_CART_TOTAL!(), "30.00", "70.00")));
+        assert!(summary.ends_with(&format!(STORE_CART_MARKUP!(), "10.00")));
+
+        let summary = Store::independence().cart_summary(&cart, Money::from_dollars(100));
+        assert!(!summary.contains("more than the same goods cost in Independence"));
+    }
+}
@@ -0,0 +1,26 @@
+//! The longer passages of text shown by the game, kept in the strings directory
+
+/// The title banner and instructions shown when the game starts
+pub const BANNER: &str = include_str!("../strings/banner.txt");
+/// The question asking how good a shot the player is
+pub const ASK_MARKSMAN: &str = include_str!("../strings/ask_marksman.txt");
+
+/// Shown on arriving in Oregon City, before the summary of the trip
+pub const COMPLETE_PREFIX: &str = include_str!("../strings/complete_prefix.txt");
+/// Shown on arriving in Oregon City, after the summary of the trip
+pub const COMPLETE_SUFFIX: &str = include_str!("../strings/complete_suffix.txt");
+
+/// Shown when the party dies, after the epitaph and before the funeral arrangements
+pub const DEATH_PREFIX: &str = include_str!("../strings/death_prefix.txt");
+/// Shown when the party dies, at the very end of the game
+pub const DEATH_SUFFIX: &str = include_str!("../strings/death_suffix.txt");
+
+/// The epitaphs for each way the party can die
+pub const DEATH_STARVATION: &str = include_str!("../strings/death_starvation.txt");
+pub const DEATH_PNEUMONIA: &str = include_str!("../strings/death_pneumonia.txt");
+pub const DEATH_INJURIES: &str = include_str!("../strings/death_injuries.txt");
+pub const DEATH_BLIZZARD: &str = include_str!("../strings/death_blizzard.txt");
+pub const DEATH_EXPOSURE: &str = include_str!("../strings/death_exposure.txt");
+pub const DEATH_RIDERS: &str = include_str!("../strings/death_riders.txt");
+pub const DEATH_SNAKEBITE: &str = include_str!("../strings/death_snakebite.txt");
+pub const DEATH_OUT_OF_TIME: &str = include_str!("../strings/death_out_of_time.txt");
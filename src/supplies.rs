@@ -1,23 +1,115 @@
-use std::io::{Cursor, Write, BufRead, Seek, SeekFrom};
+use std::io::Write;
+#[cfg(test)]
+use std::io::Cursor;
 use std::fmt;
 use std::error::Error;
 use std::result::Result;
-use crate::*;
-
-const ASK_FOOD_SPEND: &str = "How much do you want to spend on \x1B[36mFood\x1B[0m? ";
-const ASK_AMMO_SPEND: &str = "How much do you want to spend on \x1B[35mAmmunition\x1B[0m? ";
-const ASK_CLOTHES_SPEND: &str = "How much do you want to spend on \x1B[34mClothing\x1B[0m? ";
-const ASK_MISC_SPEND: &str = "How much do you want to spend on \x1B[33mMiscellaneous supplies\x1B[0m? ";
+use crate::money::*;
+use crate::prompt::*;
+use crate::save::*;
+use crate::store::*;
+use crate::trip::*;
+
+pub const ASK_OXEN_SPEND: &str = "How much do you want to spend on your \x1B[31mOxen team\x1B[0m? ";
+pub const ASK_FOOD_SPEND: &str = "How much do you want to spend on \x1B[36mFood\x1B[0m? ";
+pub const ASK_AMMO_SPEND: &str = "How much do you want to spend on \x1B[35mAmmunition\x1B[0m? ";
+pub const ASK_CLOTHES_SPEND: &str = "How much do you want to spend on \x1B[34mClothing\x1B[0m? ";
+pub const ASK_MISC_SPEND: &str = "How much do you want to spend on \x1B[33mMiscellaneous supplies\x1B[0m? ";
+/// The least that can be spent on an oxen team
+pub const OXEN_MIN_SPEND: Money = Money::from_dollars(200);
+/// The most that can be spent on an oxen team
+pub const OXEN_MAX_SPEND: Money = Money::from_dollars(300);
+/// A dollar's worth of ammunition is a belt of 50 bullets
+pub const BULLETS_PER_DOLLAR: u32 = 50;
+
+pub const ASK_CART: &str = "Do you want to 1) Buy these supplies, 2) Change an item or 3) Cancel? ";
+pub const ASK_CART_ITEM: &str = "Which do you want to change: 1) Food, 2) Ammunition, 3) Clothing or 4) Miscellaneous supplies? ";
+
+const SUPPLIES_BUY_CANCELLED: &str = "Nothing was bought\n";
 macro_rules! SUPPLIES_BUY_LEFT { () => { "After all your purchases, you now have $\x1B[32m{}\x1B[0m left\n" }; }
 
 pub struct Supplies {
-    money: u32,
-    oxen: u32,
+    money: Money,
+    oxen: OxenCondition,
     food: u32,
+    /// Ammunition is counted in bullets, not in the dollars spent on it
     ammo: u32,
     clothes: u32,
     misc: u32,
-    cost_premium: f32,
+    misc_history: Vec<MiscConsumption>,
+}
+
+/// The goods that can be bought at a store
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum SupplyItem {
+    Food,
+    Bullets,
+    Clothing,
+    Misc,
+}
+
+impl SupplyItem {
+    /// Every item a store sells, in the order they're shopped for
+    pub const ALL: [SupplyItem; 4] = [SupplyItem::Food, SupplyItem::Bullets, SupplyItem::Clothing, SupplyItem::Misc];
+
+    /// The name of the item, as shown on a price sheet
+    pub fn name(&self) -> &'static str {
+        match *self {
+            SupplyItem::Food => "\x1B[36mFood\x1B[0m",
+            SupplyItem::Bullets => "\x1B[35mBullets\x1B[0m",
+            SupplyItem::Clothing => "\x1B[34mClothing\x1B[0m",
+            SupplyItem::Misc => "\x1B[33mMisc\x1B[0m",
+        }
+    }
+
+    /// The unit the item is sold in, which is what $1 buys in Independence
+    pub fn unit(&self) -> &'static str {
+        match *self {
+            SupplyItem::Food => "ration",
+            SupplyItem::Bullets => "belt of 50",
+            SupplyItem::Clothing => "garment",
+            SupplyItem::Misc => "kit",
+        }
+    }
+
+    /// How many of the item are in each unit sold
+    pub fn per_unit(&self) -> u32 {
+        match *self {
+            SupplyItem::Bullets => BULLETS_PER_DOLLAR,
+            _ => 1,
+        }
+    }
+
+    /// The question asking how much to spend on the item
+    pub fn ask_spend(&self) -> &'static str {
+        match *self {
+            SupplyItem::Food => ASK_FOOD_SPEND,
+            SupplyItem::Bullets => ASK_AMMO_SPEND,
+            SupplyItem::Clothing => ASK_CLOTHES_SPEND,
+            SupplyItem::Misc => ASK_MISC_SPEND,
+        }
+    }
+}
+
+/// What miscellaneous supplies were used up for
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum MiscUse {
+    Repairs,
+    Medicine,
+    Lost,
+}
+
+/// A record of miscellaneous supplies being used up
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct MiscConsumption {
+    pub reason: MiscUse,
+    pub amount: u32,
 }
 
 #[derive(PartialEq)]
@@ -26,19 +118,46 @@ pub enum BuyErrorType {
     TooLittle,
     TooMuch,
     InsufficientFunds,
+    OutOfStock,
 }
 
 #[derive(Debug)]
 pub struct BuyError {
-    requested: u32,
-    available: u32,
-    min_required: u32,
-    max_allowed: u32,
+    requested: Money,
+    available: Money,
+    min_required: Money,
+    max_allowed: Money,
     reason: BuyErrorType,
 }
 
 impl Error for BuyError {}
 
+impl BuyError {
+    /// There isn't enough money to spend the amount requested
+    pub fn insufficient_funds(requested: Money, available: Money) -> BuyError {
+        BuyError{ min_required: Money::ZERO, max_allowed: available, requested, available, reason: BuyErrorType::InsufficientFunds }
+    }
+
+    /// The store doesn't have enough in stock, spending at most `max_allowed` would clear it out
+    pub fn out_of_stock(max_allowed: Money, requested: Money, available: Money) -> BuyError {
+        BuyError{ min_required: Money::ZERO, max_allowed, requested, available, reason: BuyErrorType::OutOfStock }
+    }
+}
+
+#[derive(Debug)]
+pub struct EatError {
+    requested: u32,
+    available: u32,
+}
+
+impl Error for EatError {}
+
+impl fmt::Display for EatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\tYou can't eat that well, eating {} food with only {} left", self.requested, self.available)
+    }
+}
+
 impl fmt::Display for BuyError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.reason {
@@ -48,41 +167,87 @@ impl fmt::Display for BuyError {
                 write!(f, "\tYou may not spend more than ${}", self.max_allowed),
             BuyErrorType::InsufficientFunds =>
                 write!(f, "\tUnable to spend ${}, you only have ${} available.", self.requested, self.available),
+            BuyErrorType::OutOfStock =>
+                write!(f, "\tThe store doesn't have that much, you may not spend more than ${}", self.max_allowed),
         }
     }
 }
 
 impl fmt::Display for Supplies {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "\t{}\t{}\t{}\t{}\t{}\n\t{}\t{}\t{}\t{}\t{}\n",
-            "\x1B[36mFood\x1B[0m", "\x1B[35mAmmo\x1B[0m", "\x1B[34mClothes\x1B[0m",
-            "\x1B[33mMisc\x1B[0m", "\x1B[32mMoney\x1B[0m",
+        write!(f, "\t\x1B[36mFood\x1B[0m\t\x1B[35mBullets\x1B[0m\t\x1B[34mClothes\x1B[0m\t\x1B[33mMisc\x1B[0m\t\x1B[32mMoney\x1B[0m\n\t{}\t{}\t{}\t{}\t{}\n",
             self.food, self.ammo, self.clothes, self.misc, self.money)
     }
 }
 
+impl Default for Supplies {
+    fn default() -> Supplies {
+        Supplies::new()
+    }
+}
+
 impl Supplies {
     pub fn new() -> Supplies {
         Supplies {
-            money: 700,
-            oxen: 0,
+            money: Money::from_dollars(700),
+            oxen: OxenCondition::new(0),
             food: 0,
             ammo: 0,
             clothes: 0,
             misc: 0,
-            cost_premium: 0.0,
+            misc_history: Vec::new(),
         }
     }
 
-    pub fn set_premium(&mut self, premium: f32) {
-        self.cost_premium = premium;
-    }
-
-    pub fn money_left(&mut self) -> u32 {
+    /// Save the supplies, including the record of miscellaneous supplies used
+    ///     Money is saved in cents, the history as `reason:amount` separated by spaces
+    pub fn save(&self, file: &mut SaveFile) {
+        file.set("supplies.money", self.money.cents());
+        file.set("supplies.oxen", self.oxen.value());
+        file.set("supplies.food", self.food);
+        file.set("supplies.ammo", self.ammo);
+        file.set("supplies.clothes", self.clothes);
+        file.set("supplies.misc", self.misc);
+        let history: Vec<String> = self.misc_history.iter().map(|used| format!("{:?}:{}", used.reason, used.amount)).collect();
+        file.set("supplies.misc_history", history.join(" "));
+    }
+
+    /// Load the supplies, as saved
+    pub fn load(file: &SaveFile) -> Result<Supplies, SaveError> {
+        let history = file.text("supplies.misc_history")?;
+        let invalid_history = || SaveError::Invalid("supplies.misc_history".to_string(), history.to_string());
+        let misc_history = history.split_whitespace().map(|used| {
+            let (reason, amount) = used.split_once(':').ok_or_else(invalid_history)?;
+            let reason = match reason {
+                "Repairs" => MiscUse::Repairs,
+                "Medicine" => MiscUse::Medicine,
+                "Lost" => MiscUse::Lost,
+                _ => return Err(invalid_history()),
+            };
+            Ok(MiscConsumption { reason, amount: amount.parse().map_err(|_| invalid_history())? })
+        }).collect::<Result<Vec<MiscConsumption>, SaveError>>()?;
+
+        Ok(Supplies {
+            money: Money::from_cents(file.get("supplies.money")?),
+            oxen: OxenCondition::new(file.get("supplies.oxen")?),
+            food: file.get("supplies.food")?,
+            ammo: file.get("supplies.ammo")?,
+            clothes: file.get("supplies.clothes")?,
+            misc: file.get("supplies.misc")?,
+            misc_history,
+        })
+    }
+
+    pub fn money_left(&mut self) -> Money {
         self.money
     }
 
     pub fn oxen_left(&mut self) -> u32 {
+        self.oxen.value()
+    }
+
+    /// Retrieve the condition of the oxen team, which sets the pace of travel
+    pub fn oxen_condition(&self) -> OxenCondition {
         self.oxen
     }
 
@@ -102,61 +267,172 @@ impl Supplies {
         self.misc
     }
 
-    pub fn buy_oxen(&mut self, spend: u32) -> Result<(), BuyError> {
-        if spend > self.money {
-            return Err(BuyError{ min_required: 200, max_allowed: 300, requested: spend, available: self.money, reason: BuyErrorType::InsufficientFunds });
-        } else if spend < 200 {
-            return Err(BuyError{ min_required: 200, max_allowed: 300, requested: spend, available: self.money, reason: BuyErrorType::TooLittle });
-        } else if spend > 300 {
-            return Err(BuyError{ min_required: 200, max_allowed: 300, requested: spend, available: self.money, reason: BuyErrorType::TooMuch });
-        }
-        self.oxen += spend;
-        self.money -= spend;
-        Ok(())    
+    /// Add food to the wagon (e.g. from a successful hunt)
+    pub fn add_food(&mut self, amount: u32) {
+        self.food += amount;
     }
 
-    pub fn buy_food(&mut self, spend: u32) -> Result<(), BuyError> {
-        if spend > self.money {
-            return Err(BuyError{ min_required: 0, max_allowed: self.money, requested: spend, available: self.money, reason: BuyErrorType::InsufficientFunds });
+    /// Eat a turn's worth of food, refusing to eat more food than is left
+    pub fn eat(&mut self, food: u32) -> Result<(), EatError> {
+        if food > self.food {
+            return Err(EatError{ requested: food, available: self.food });
         }
-        self.food += (spend as f32 * (1.0 - self.cost_premium)) as u32;
-        self.money -= spend;
-        Ok(())    
+        self.food -= food;
+        Ok(())
     }
 
-    pub fn buy_ammo(&mut self, spend: u32) -> Result<(), BuyError> {
-        if spend > self.money {
-            return Err(BuyError{ min_required: 0, max_allowed: self.money, requested: spend, available: self.money, reason: BuyErrorType::InsufficientFunds });
+    /// Use up miscellaneous supplies to repair the wagon
+    ///     Returns false if there wasn't enough, in which case everything left is used up
+    pub fn use_repairs(&mut self, amount: u32) -> bool {
+        self.use_misc(MiscUse::Repairs, amount)
+    }
+
+    /// Use up miscellaneous supplies as medicine
+    ///     Returns false if there wasn't enough, in which case everything left is used up
+    pub fn use_medicine(&mut self, amount: u32) -> bool {
+        self.use_misc(MiscUse::Medicine, amount)
+    }
+
+    /// Lose miscellaneous supplies (e.g. damaged or left behind), running out entirely if more is lost than is left
+    pub fn lose_misc(&mut self, amount: u32) {
+        self.use_misc(MiscUse::Lost, amount);
+    }
+
+    /// Retrieve the history of miscellaneous supplies used up along the trip
+    pub fn misc_history(&self) -> &[MiscConsumption] {
+        &self.misc_history
+    }
+
+    /// Retrieve the total miscellaneous supplies used up for a reason
+    pub fn misc_used(&self, reason: MiscUse) -> u32 {
+        self.misc_history.iter().filter(|used| used.reason == reason).map(|used| used.amount).sum()
+    }
+
+    fn use_misc(&mut self, reason: MiscUse, amount: u32) -> bool {
+        let used = amount.min(self.misc);
+        if used > 0 {
+            self.misc_history.push(MiscConsumption{ reason, amount: used });
         }
-        self.ammo += (spend as f32 * (1.0 - self.cost_premium)) as u32;
-        self.money -= spend;
-        Ok(())    
+        self.misc -= used;
+        used == amount
     }
 
-    pub fn buy_clothes(&mut self, spend: u32) -> Result<(), BuyError> {
+    /// Pay for a service (e.g. the doctor) from the money left
+    pub fn pay(&mut self, cost: Money) -> Result<(), BuyError> {
+        self.money = self.money.checked_sub(cost).ok_or_else(|| BuyError::insufficient_funds(cost, self.money))?;
+        Ok(())
+    }
+
+    /// Use up bullets, running out entirely if more is used than is left
+    ///     Returns false if there wasn't enough
+    pub fn use_ammo(&mut self, amount: u32) -> bool {
+        let enough = amount <= self.ammo;
+        self.ammo = self.ammo.saturating_sub(amount);
+        enough
+    }
+
+    /// Lose food (e.g. spoiled or stolen), running out entirely if more is lost than is left
+    pub fn lose_food(&mut self, amount: u32) {
+        self.food = self.food.saturating_sub(amount);
+    }
+
+    /// Lose clothing (e.g. washed away or torn to shreds), running out entirely if more is lost than is left
+    pub fn lose_clothes(&mut self, amount: u32) {
+        self.clothes = self.clothes.saturating_sub(amount);
+    }
+
+    /// Lose money (e.g. stolen by bandits), running out entirely if more is lost than is left
+    pub fn lose_money(&mut self, amount: Money) {
+        self.money = self.money.saturating_sub(amount);
+    }
+
+    /// Lose part of the oxen team (e.g. injured, stolen or run into the ground)
+    pub fn lose_oxen(&mut self, amount: u32) {
+        self.oxen.injure(amount);
+    }
+
+    pub fn buy_oxen(&mut self, spend: Money) -> Result<(), BuyError> {
+        let (min_required, max_allowed) = (OXEN_MIN_SPEND, OXEN_MAX_SPEND);
         if spend > self.money {
-            return Err(BuyError{ min_required: 0, max_allowed: self.money, requested: spend, available: self.money, reason: BuyErrorType::InsufficientFunds });
+            return Err(BuyError{ min_required, max_allowed, requested: spend, available: self.money, reason: BuyErrorType::InsufficientFunds });
+        } else if spend < min_required {
+            return Err(BuyError{ min_required, max_allowed, requested: spend, available: self.money, reason: BuyErrorType::TooLittle });
+        } else if spend > max_allowed {
+            return Err(BuyError{ min_required, max_allowed, requested: spend, available: self.money, reason: BuyErrorType::TooMuch });
         }
-        self.clothes += (spend as f32 * (1.0 - self.cost_premium)) as u32;
-        self.money -= spend;
+        self.pay(spend)?;
+        self.oxen.strengthen(spend.dollars());
         Ok(())    
     }
 
-    pub fn buy_misc(&mut self, spend: u32) -> Result<(), BuyError> {
-        if spend > self.money {
-            return Err(BuyError{ min_required: 0, max_allowed: self.money, requested: spend, available: self.money, reason: BuyErrorType::InsufficientFunds });
+    /// Buy food from a store, out of its stock
+    pub fn buy_food(&mut self, store: &mut Store, spend: Money) -> Result<(), BuyError> {
+        store.sell(self, SupplyItem::Food, spend)
+    }
+
+    /// Buy belts of ammunition from a store, out of its stock
+    pub fn buy_ammo(&mut self, store: &mut Store, spend: Money) -> Result<(), BuyError> {
+        store.sell(self, SupplyItem::Bullets, spend)
+    }
+
+    /// Buy clothing from a store, out of its stock
+    pub fn buy_clothes(&mut self, store: &mut Store, spend: Money) -> Result<(), BuyError> {
+        store.sell(self, SupplyItem::Clothing, spend)
+    }
+
+    /// Buy miscellaneous supplies from a store, out of its stock
+    pub fn buy_misc(&mut self, store: &mut Store, spend: Money) -> Result<(), BuyError> {
+        store.sell(self, SupplyItem::Misc, spend)
+    }
+
+    /// Take delivery of goods bought at a store
+    pub fn stock_up(&mut self, item: SupplyItem, quantity: u32) {
+        match item {
+            SupplyItem::Food => self.food += quantity,
+            SupplyItem::Bullets => self.ammo += quantity,
+            SupplyItem::Clothing => self.clothes += quantity,
+            SupplyItem::Misc => self.misc += quantity,
         }
-        self.misc += (spend as f32 * (1.0 - self.cost_premium)) as u32;
-        self.money -= spend;
-        Ok(())    
     }
 
-    pub fn buy<W: Write, R: BufRead>(&mut self, out: &mut W, input: &mut R) {
-        ask_ok!(self.buy_food(ask!(ASK_FOOD_SPEND, out, input)));
-        ask_ok!(self.buy_ammo(ask!(ASK_AMMO_SPEND, out, input)));
-        ask_ok!(self.buy_clothes(ask!(ASK_CLOTHES_SPEND, out, input)));
-        ask_ok!(self.buy_misc(ask!(ASK_MISC_SPEND, out, input)));
-        out.write(format!(SUPPLIES_BUY_LEFT!(), self.money_left()).as_bytes()).unwrap();
+    /// Go shopping at a store, showing its price sheet and then asking how much to spend on each item
+    ///     The order is summed up before anything is bought, so any item can be changed or the order cancelled
+    ///     Any item the store refuses is explained, and the question asked again
+    ///     Returns whether the order was bought, false if it was cancelled
+    pub fn buy<W: Write, P: Prompter>(&mut self, store: &mut Store, out: &mut W, prompter: &mut P) -> Result<bool, PromptError> {
+        out.write_all(format!("{}", store).as_bytes()).unwrap();
+        let mut cart = Cart::new();
+        for item in SupplyItem::ALL {
+            self.fill_cart(store, &mut cart, item, out, prompter)?;
+        }
+
+        let bought = loop {
+            out.write_all(store.cart_summary(&cart, self.money_left()).as_bytes()).unwrap();
+            match prompter.menu(ASK_CART, 3, out)? {
+                1 => match store.sell_cart(self, &cart) {
+                    Ok(()) => break true,
+                    Err(e) => writeln!(out, "{}", e)?,
+                },
+                2 => {
+                    let item = SupplyItem::ALL[prompter.menu(ASK_CART_ITEM, 4, out)? as usize - 1];
+                    self.fill_cart(store, &mut cart, item, out, prompter)?;
+                },
+                _ => {
+                    out.write_all(SUPPLIES_BUY_CANCELLED.as_bytes()).unwrap();
+                    break false;
+                },
+            }
+        };
+        out.write_all(format!(SUPPLIES_BUY_LEFT!(), self.money_left()).as_bytes()).unwrap();
+        Ok(bought)
+    }
+
+    /// Ask how much to spend on an item, putting it in the cart once the store accepts it
+    fn fill_cart<W: Write, P: Prompter>(&self, store: &Store, cart: &mut Cart, item: SupplyItem,
+            out: &mut W, prompter: &mut P) -> Result<(), PromptError> {
+        let available = self.money;
+        prompter.number_accepted(item.ask_spend(), 0..=Money::MAX_DOLLARS, out,
+            |spend| store.add_to_cart(cart, item, Money::from_dollars(spend), available))
     }
 }
 
@@ -166,287 +442,493 @@ mod tests {
 
     #[test]
     fn test_buyerror_toolittle() {
-        let buy_error = BuyError{ min_required: 0, max_allowed: 0, requested: 0, available: 0, reason: BuyErrorType::TooLittle };
+        let buy_error = BuyError{ min_required: Money::ZERO, max_allowed: Money::ZERO, requested: Money::ZERO, available: Money::ZERO, reason: BuyErrorType::TooLittle };
         println!("{}", buy_error)
     }
 
     #[test]
     fn test_buyerror_toomuch() {
-        let buy_error = BuyError{ min_required: 0, max_allowed: 0, requested: 0, available: 0, reason: BuyErrorType::TooMuch };
+        let buy_error = BuyError{ min_required: Money::ZERO, max_allowed: Money::ZERO, requested: Money::ZERO, available: Money::ZERO, reason: BuyErrorType::TooMuch };
         println!("{}", buy_error)
     }
 
     #[test]
     fn test_buyerror_insufficient() {
-        let buy_error = BuyError{ min_required: 0, max_allowed: 0, requested: 0, available: 0, reason: BuyErrorType::InsufficientFunds };
+        let buy_error = BuyError{ min_required: Money::ZERO, max_allowed: Money::ZERO, requested: Money::ZERO, available: Money::ZERO, reason: BuyErrorType::InsufficientFunds };
         println!("{}", buy_error)
     }
 
+    #[test]
+    fn test_buyerror_out_of_stock() {
+        let buy_error = BuyError::out_of_stock(Money::from_cents(22649), Money::from_dollars(300), Money::from_dollars(700));
+        assert_eq!(BuyErrorType::OutOfStock, buy_error.reason);
+        assert_eq!("\tThe store doesn't have that much, you may not spend more than $226.49", format!("{}", buy_error));
+    }
+
     #[test]
     fn test_supplies_constructor() {
         let supplies = Supplies::new();
     
-        assert_eq!(700, supplies.money);
-        assert_eq!(0, supplies.oxen);
+        assert_eq!(Money::from_dollars(700), supplies.money);
+        assert_eq!(0, supplies.oxen.value());
     }
 
     #[test]
     fn test_supplies_money_left() {
         let mut supplies = Supplies::new();
-        supplies.buy_oxen(200).unwrap();
-        assert_eq!(500, supplies.money_left());
+        supplies.buy_oxen(Money::from_dollars(200)).unwrap();
+        assert_eq!(Money::from_dollars(500), supplies.money_left());
     }
 
     #[test]
     fn test_supplies_oxen_left() {
         let mut supplies = Supplies::new();
-        supplies.buy_oxen(250).unwrap();
+        supplies.buy_oxen(Money::from_dollars(250)).unwrap();
         assert_eq!(250, supplies.oxen_left());
     }
 
     #[test]
     fn test_supplies_food_left() {
         let mut supplies = Supplies::new();
-        supplies.buy_food(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_food(&mut store, Money::from_dollars(200)).unwrap();
         assert_eq!(200, supplies.food_left());
     }
 
     #[test]
     fn test_supplies_ammo_left() {
         let mut supplies = Supplies::new();
-        supplies.buy_ammo(150).unwrap();
-        assert_eq!(150, supplies.ammo_left());
+        let mut store = Store::independence();
+        supplies.buy_ammo(&mut store, Money::from_dollars(150)).unwrap();
+        assert_eq!(150 * BULLETS_PER_DOLLAR, supplies.ammo_left());
+        assert_eq!(Money::from_dollars(550), supplies.money_left());
     }
 
     #[test]
     fn test_supplies_clothes_left() {
         let mut supplies = Supplies::new();
-        supplies.buy_clothes(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_clothes(&mut store, Money::from_dollars(200)).unwrap();
         assert_eq!(200, supplies.clothes_left());
     }
 
     #[test]
     fn test_supplies_misc_left() {
         let mut supplies = Supplies::new();
-        supplies.buy_misc(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_misc(&mut store, Money::from_dollars(200)).unwrap();
         assert_eq!(200, supplies.misc_left());
     }
 
+    #[test]
+    fn test_supplies_add_food() {
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_food(&mut store, Money::from_dollars(100)).unwrap();
+        supplies.add_food(50);
+        assert_eq!(150, supplies.food_left());
+    }
+
+    #[test]
+    fn test_eaterror() {
+        let eat_error = EatError{ requested: 23, available: 13 };
+        assert_eq!("\tYou can't eat that well, eating 23 food with only 13 left", format!("{}", eat_error));
+    }
+
+    #[test]
+    fn test_supplies_eat() {
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_food(&mut store, Money::from_dollars(100)).unwrap();
+        supplies.eat(18).unwrap();
+        assert_eq!(82, supplies.food_left());
+    }
+
+    #[test]
+    fn test_supplies_eat_too_much() {
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_food(&mut store, Money::from_dollars(20)).unwrap();
+        let eat_error = supplies.eat(23).unwrap_err();
+        assert_eq!(23, eat_error.requested);
+        assert_eq!(20, eat_error.available);
+        assert_eq!(20, supplies.food_left());
+    }
+
+    #[test]
+    fn test_supplies_use_repairs() {
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_misc(&mut store, Money::from_dollars(10)).unwrap();
+        assert!(supplies.use_repairs(4));
+        assert_eq!(6, supplies.misc_left());
+        assert!(!supplies.use_repairs(10));
+        assert_eq!(0, supplies.misc_left());
+        assert_eq!(10, supplies.misc_used(MiscUse::Repairs));
+    }
+
+    #[test]
+    fn test_supplies_use_medicine() {
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_misc(&mut store, Money::from_dollars(10)).unwrap();
+        assert!(supplies.use_medicine(5));
+        assert!(!supplies.use_medicine(10));
+        assert!(!supplies.use_medicine(1));
+        assert_eq!(0, supplies.misc_left());
+        assert_eq!(10, supplies.misc_used(MiscUse::Medicine));
+    }
+
+    #[test]
+    fn test_supplies_misc_history() {
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_misc(&mut store, Money::from_dollars(20)).unwrap();
+        supplies.use_repairs(8);
+        supplies.use_medicine(2);
+        supplies.lose_misc(15);
+        supplies.use_medicine(5);
+
+        assert_eq!(&[
+            MiscConsumption{ reason: MiscUse::Repairs, amount: 8 },
+            MiscConsumption{ reason: MiscUse::Medicine, amount: 2 },
+            MiscConsumption{ reason: MiscUse::Lost, amount: 10 },
+        ], supplies.misc_history());
+        assert_eq!(10, supplies.misc_used(MiscUse::Lost));
+    }
+
+    #[test]
+    fn test_supplies_pay() {
+        let mut supplies = Supplies::new();
+        supplies.pay(Money::from_dollars(20)).unwrap();
+        assert_eq!(Money::from_dollars(680), supplies.money_left());
+    }
+
+    #[test]
+    fn test_supplies_pay_insufficient() {
+        let mut supplies = Supplies::new();
+        let reason = supplies.pay(Money::from_dollars(701)).unwrap_err().reason;
+        assert_eq!(BuyErrorType::InsufficientFunds, reason);
+        assert_eq!(Money::from_dollars(700), supplies.money_left());
+    }
+
+    #[test]
+    fn test_supplies_use_ammo() {
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_ammo(&mut store, Money::from_dollars(2)).unwrap();
+        assert!(supplies.use_ammo(40));
+        assert_eq!(60, supplies.ammo_left());
+        assert!(!supplies.use_ammo(100));
+        assert_eq!(0, supplies.ammo_left());
+    }
+
+    #[test]
+    fn test_supplies_lose_food() {
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_food(&mut store, Money::from_dollars(50)).unwrap();
+        supplies.lose_food(30);
+        assert_eq!(20, supplies.food_left());
+        supplies.lose_food(30);
+        assert_eq!(0, supplies.food_left());
+    }
+
+    #[test]
+    fn test_supplies_lose_clothes() {
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_clothes(&mut store, Money::from_dollars(50)).unwrap();
+        supplies.lose_clothes(20);
+        assert_eq!(30, supplies.clothes_left());
+        supplies.lose_clothes(40);
+        assert_eq!(0, supplies.clothes_left());
+    }
+
+    #[test]
+    fn test_supplies_pay_cents() {
+        let mut supplies = Supplies::new();
+        supplies.pay(Money::from_cents(450)).unwrap();
+        assert_eq!(Money::from_cents(69550), supplies.money_left());
+    }
+
+    #[test]
+    fn test_supplies_lose_money() {
+        let mut supplies = Supplies::new();
+        supplies.lose_money(Money::from_dollars(200));
+        assert_eq!(Money::from_dollars(500), supplies.money_left());
+        supplies.lose_money(Money::from_dollars(600));
+        assert_eq!(Money::from_dollars(0), supplies.money_left());
+    }
+
+    #[test]
+    fn test_supplies_lose_oxen() {
+        let mut supplies = Supplies::new();
+        supplies.buy_oxen(Money::from_dollars(220)).unwrap();
+        supplies.lose_oxen(40);
+        assert_eq!(180, supplies.oxen_left());
+        supplies.lose_oxen(200);
+        assert_eq!(0, supplies.oxen_left());
+    }
+
     #[test]
     fn test_supplies_buy_oxen_success() {
         let mut supplies = Supplies::new();
-        supplies.buy_oxen(200).unwrap();
+        supplies.buy_oxen(Money::from_dollars(200)).unwrap();
     
-        assert_eq!(500, supplies.money);
-        assert_eq!(200, supplies.oxen);
+        assert_eq!(Money::from_dollars(500), supplies.money);
+        assert_eq!(200, supplies.oxen.value());
     }
 
     #[test]
     fn test_supplies_buy_oxen_too_little() {
         let mut supplies = Supplies::new();
-        let reason = supplies.buy_oxen(0).unwrap_err().reason;
+        let reason = supplies.buy_oxen(Money::from_dollars(0)).unwrap_err().reason;
 
         assert_eq!(BuyErrorType::TooLittle, reason);
-        assert_eq!(700, supplies.money);
-        assert_eq!(0, supplies.oxen);
+        assert_eq!(Money::from_dollars(700), supplies.money);
+        assert_eq!(0, supplies.oxen.value());
     }
 
     #[test]
     fn test_supplies_buy_oxen_too_much() {
         let mut supplies = Supplies::new();
-        let reason = supplies.buy_oxen(301).unwrap_err().reason;
+        let reason = supplies.buy_oxen(Money::from_dollars(301)).unwrap_err().reason;
 
         assert_eq!(BuyErrorType::TooMuch, reason);
-        assert_eq!(700, supplies.money);
-        assert_eq!(0, supplies.oxen);
+        assert_eq!(Money::from_dollars(700), supplies.money);
+        assert_eq!(0, supplies.oxen.value());
     }
 
     #[test]
     fn test_supplies_buy_oxen_insufficient() {
         let mut supplies = Supplies::new();
-        let reason = supplies.buy_oxen(1000).unwrap_err().reason;
+        let reason = supplies.buy_oxen(Money::from_dollars(1000)).unwrap_err().reason;
 
         assert_eq!(BuyErrorType::InsufficientFunds, reason);
-        assert_eq!(700, supplies.money);
-        assert_eq!(0, supplies.oxen);
+        assert_eq!(Money::from_dollars(700), supplies.money);
+        assert_eq!(0, supplies.oxen.value());
     }
 
     #[test]
     fn test_supplies_buy_food_success() {
         let mut supplies = Supplies::new();
-        supplies.buy_food(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_food(&mut store, Money::from_dollars(200)).unwrap();
     
-        assert_eq!(500, supplies.money);
+        assert_eq!(Money::from_dollars(500), supplies.money);
         assert_eq!(200, supplies.food);
     }
 
     #[test]
     fn test_supplies_buy_food_twice() {
         let mut supplies = Supplies::new();
-        supplies.buy_food(200).unwrap();
-        supplies.buy_food(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_food(&mut store, Money::from_dollars(200)).unwrap();
+        supplies.buy_food(&mut store, Money::from_dollars(200)).unwrap();
     
-        assert_eq!(300, supplies.money);
+        assert_eq!(Money::from_dollars(300), supplies.money);
         assert_eq!(400, supplies.food);
     }
 
+    #[test]
+    fn test_supplies_buy_food_whole_units() {
+        let mut supplies = Supplies::new();
+        let mut store = Store::independence();
+        supplies.buy_food(&mut store, Money::from_cents(150)).unwrap();
+
+        assert_eq!(Money::from_dollars(699), supplies.money);
+        assert_eq!(1, supplies.food);
+    }
+
     #[test]
     fn test_supplies_buy_food_insufficient() {
         let mut supplies = Supplies::new();
-        let reason = supplies.buy_food(1000).unwrap_err().reason;
+        let mut store = Store::independence();
+        let reason = supplies.buy_food(&mut store, Money::from_dollars(1000)).unwrap_err().reason;
 
         assert_eq!(BuyErrorType::InsufficientFunds, reason);
-        assert_eq!(700, supplies.money);
+        assert_eq!(Money::from_dollars(700), supplies.money);
         assert_eq!(0, supplies.food);
     }
 
     #[test]
     fn test_supplies_buy_ammo_success() {
         let mut supplies = Supplies::new();
-        supplies.buy_ammo(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_ammo(&mut store, Money::from_dollars(200)).unwrap();
     
-        assert_eq!(500, supplies.money);
-        assert_eq!(200, supplies.ammo);
+        assert_eq!(Money::from_dollars(500), supplies.money);
+        assert_eq!(200 * BULLETS_PER_DOLLAR, supplies.ammo);
     }
 
     #[test]
     fn test_supplies_buy_ammo_twice() {
         let mut supplies = Supplies::new();
-        supplies.buy_ammo(200).unwrap();
-        supplies.buy_ammo(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_ammo(&mut store, Money::from_dollars(200)).unwrap();
+        supplies.buy_ammo(&mut store, Money::from_dollars(200)).unwrap();
     
-        assert_eq!(300, supplies.money);
-        assert_eq!(400, supplies.ammo);
+        assert_eq!(Money::from_dollars(300), supplies.money);
+        assert_eq!(400 * BULLETS_PER_DOLLAR, supplies.ammo);
     }
 
     #[test]
     fn test_supplies_buy_ammo_insufficient() {
         let mut supplies = Supplies::new();
-        let reason = supplies.buy_ammo(1000).unwrap_err().reason;
+        let mut store = Store::independence();
+        let reason = supplies.buy_ammo(&mut store, Money::from_dollars(1000)).unwrap_err().reason;
 
         assert_eq!(BuyErrorType::InsufficientFunds, reason);
-        assert_eq!(700, supplies.money);
+        assert_eq!(Money::from_dollars(700), supplies.money);
         assert_eq!(0, supplies.ammo);
     }
 
     #[test]
     fn test_supplies_buy_clothes_success() {
         let mut supplies = Supplies::new();
-        supplies.buy_clothes(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_clothes(&mut store, Money::from_dollars(200)).unwrap();
     
-        assert_eq!(500, supplies.money);
+        assert_eq!(Money::from_dollars(500), supplies.money);
         assert_eq!(200, supplies.clothes);
     }
 
     #[test]
     fn test_supplies_buy_clothes_twice() {
         let mut supplies = Supplies::new();
-        supplies.buy_clothes(200).unwrap();
-        supplies.buy_clothes(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_clothes(&mut store, Money::from_dollars(200)).unwrap();
+        supplies.buy_clothes(&mut store, Money::from_dollars(200)).unwrap();
     
-        assert_eq!(300, supplies.money);
+        assert_eq!(Money::from_dollars(300), supplies.money);
         assert_eq!(400, supplies.clothes);
     }
 
     #[test]
     fn test_supplies_buy_clothes_insufficient() {
         let mut supplies = Supplies::new();
-        let reason = supplies.buy_clothes(1000).unwrap_err().reason;
+        let mut store = Store::independence();
+        let reason = supplies.buy_clothes(&mut store, Money::from_dollars(1000)).unwrap_err().reason;
 
         assert_eq!(BuyErrorType::InsufficientFunds, reason);
-        assert_eq!(700, supplies.money);
+        assert_eq!(Money::from_dollars(700), supplies.money);
         assert_eq!(0, supplies.clothes);
     }
 
     #[test]
     fn test_supplies_buy_misc_success() {
         let mut supplies = Supplies::new();
-        supplies.buy_misc(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_misc(&mut store, Money::from_dollars(200)).unwrap();
     
-        assert_eq!(500, supplies.money);
+        assert_eq!(Money::from_dollars(500), supplies.money);
         assert_eq!(200, supplies.misc);
     }
 
     #[test]
     fn test_supplies_buy_misc_twice() {
         let mut supplies = Supplies::new();
-        supplies.buy_misc(200).unwrap();
-        supplies.buy_misc(200).unwrap();
+        let mut store = Store::independence();
+        supplies.buy_misc(&mut store, Money::from_dollars(200)).unwrap();
+        supplies.buy_misc(&mut store, Money::from_dollars(200)).unwrap();
     
-        assert_eq!(300, supplies.money);
+        assert_eq!(Money::from_dollars(300), supplies.money);
         assert_eq!(400, supplies.misc);
     }
 
     #[test]
     fn test_supplies_buy_misc_insufficient() {
         let mut supplies = Supplies::new();
-        let reason = supplies.buy_misc(1000).unwrap_err().reason;
+        let mut store = Store::independence();
+        let reason = supplies.buy_misc(&mut store, Money::from_dollars(1000)).unwrap_err().reason;
 
         assert_eq!(BuyErrorType::InsufficientFunds, reason);
-        assert_eq!(700, supplies.money);
+        assert_eq!(Money::from_dollars(700), supplies.money);
         assert_eq!(0, supplies.misc);
     }   
 
     #[test]
-    fn test_supplies_set_premium_food() {
+    fn test_supplies_display() {
         let mut supplies = Supplies::new();
-        supplies.set_premium(0.3);
-        supplies.buy_food(200).unwrap();
-        assert_eq!(140, supplies.food_left());
-        assert_eq!(500, supplies.money_left());
+        let mut store = Store::independence();
+        supplies.buy_oxen(Money::from_dollars(200)).unwrap();
+        supplies.buy_food(&mut store, Money::from_dollars(10)).unwrap();
+        supplies.buy_ammo(&mut store, Money::from_dollars(20)).unwrap();
+        supplies.buy_clothes(&mut store, Money::from_dollars(30)).unwrap();
+        supplies.buy_misc(&mut store, Money::from_dollars(40)).unwrap();
+
+        let supplies_display = format!("{}", &mut supplies);
+        assert_eq!("\t\x1B[36mFood\x1B[0m\t\x1B[35mBullets\x1B[0m\t\x1B[34mClothes\x1B[0m\t\x1B[33mMisc\x1B[0m\t\x1B[32mMoney\x1B[0m\n\t10\t1000\t30\t40\t400.00\n", supplies_display);
     }
 
     #[test]
-    fn test_supplies_set_premium_ammo() {
+    fn test_supplies_stock_up() {
         let mut supplies = Supplies::new();
-        supplies.set_premium(0.3);
-        supplies.buy_ammo(200).unwrap();
-        assert_eq!(140, supplies.ammo_left());
-        assert_eq!(500, supplies.money_left());
+        supplies.stock_up(SupplyItem::Food, 10);
+        supplies.stock_up(SupplyItem::Bullets, 20);
+        supplies.stock_up(SupplyItem::Clothing, 30);
+        supplies.stock_up(SupplyItem::Misc, 40);
+        assert_eq!(10, supplies.food_left());
+        assert_eq!(20, supplies.ammo_left());
+        assert_eq!(30, supplies.clothes_left());
+        assert_eq!(40, supplies.misc_left());
+        assert_eq!(Money::from_dollars(700), supplies.money_left());
     }
 
     #[test]
-    fn test_supplies_set_premium_clothes() {
+    fn test_buy() {
         let mut supplies = Supplies::new();
-        supplies.set_premium(0.3);
-        supplies.buy_clothes(200).unwrap();
-        assert_eq!(140, supplies.clothes_left());
-        assert_eq!(500, supplies.money_left());
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = ScriptedPrompter::new(&["50", "75", "100", "150", "1"]);
+        assert_eq!(Ok(true), supplies.buy(&mut Store::independence(), &mut cout, &mut prompter));
+        assert_eq!(50, supplies.food_left());
+        assert_eq!(75 * BULLETS_PER_DOLLAR, supplies.ammo_left());
+        assert_eq!(100, supplies.clothes_left());
+        assert_eq!(150, supplies.misc_left());
     }
 
     #[test]
-    fn test_supplies_set_premium_misc() {
+    fn test_buy_refused() {
         let mut supplies = Supplies::new();
-        supplies.set_premium(0.3);
-        supplies.buy_misc(200).unwrap();
-        assert_eq!(140, supplies.misc_left());
-        assert_eq!(500, supplies.money_left());
+        supplies.pay(Money::from_dollars(300)).unwrap();
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = ScriptedPrompter::new(&["500", "50", "0", "0", "0", "1"]);
+        supplies.buy(&mut Store::independence(), &mut cout, &mut prompter).unwrap();
+        assert_eq!(50, supplies.food_left());
+        assert_eq!(Money::from_dollars(350), supplies.money_left());
+
+        let buy_out = String::from_utf8(cout.into_inner()).unwrap();
+        let refused = format!("{}\n", BuyError::insufficient_funds(Money::from_dollars(500), Money::from_dollars(400)));
+        assert!(buy_out.contains(&format!("{}{}{}", ASK_FOOD_SPEND, refused, ASK_FOOD_SPEND)));
     }
 
     #[test]
-    fn test_supplies_display() {
+    fn test_buy_change_item() {
         let mut supplies = Supplies::new();
-        supplies.buy_oxen(200).unwrap();
-        supplies.buy_food(10).unwrap();
-        supplies.buy_ammo(20).unwrap();
-        supplies.buy_clothes(30).unwrap();
-        supplies.buy_misc(40).unwrap();
+        let mut cout = Cursor::new(Vec::new());
+        let mut prompter = ScriptedPrompter::new(&["500", "50", "0", "0", "2", "1", "100", "1"]);
+        supplies.buy(&mut Store::independence(), &mut cout, &mut prompter).unwrap();
+        assert_eq!(100, supplies.food_left());
+        assert_eq!(50 * BULLETS_PER_DOLLAR, supplies.ammo_left());
+        assert_eq!(Money::from_dollars(550), supplies.money_left());
 
-        let supplies_display = format!("{}", &mut supplies);
-        assert_eq!("\t\x1B[36mFood\x1B[0m\t\x1B[35mAmmo\x1B[0m\t\x1B[34mClothes\x1B[0m\t\x1B[33mMisc\x1B[0m\t\x1B[32mMoney\x1B[0m\n\t10\t20\t30\t40\t400\n", supplies_display);
+        let buy_out = String::from_utf8(cout.into_inner()).unwrap();
+        assert_eq!(2, buy_out.matches(ASK_CART).count());
+        assert!(buy_out.ends_with(&format!(SUPPLIES_BUY_LEFT!(), Money::from_dollars(550))));
     }
 
     #[test]
-    fn test_buy() {
+    fn test_buy_cancel() {
         let mut supplies = Supplies::new();
+        let mut store = Store::independence();
         let mut cout = Cursor::new(Vec::new());
-        let mut cin = Cursor::new(Vec::new());
-        cin.write(b"50\r\n75\r\n100\r\n150\r\n").unwrap();
-        cin.seek(SeekFrom::Start(0)).unwrap();
-        supplies.buy(&mut cout, &mut cin);
-        assert_eq!(50, supplies.food_left());
-        assert_eq!(75, supplies.ammo_left());
-        assert_eq!(100, supplies.clothes_left());
-        assert_eq!(150, supplies.misc_left());
+        let mut prompter = ScriptedPrompter::new(&["500", "50", "0", "0", "3"]);
+        assert_eq!(Ok(false), supplies.buy(&mut store, &mut cout, &mut prompter));
+        assert_eq!(0, supplies.food_left());
+        assert_eq!(0, supplies.ammo_left());
+        assert_eq!(Money::from_dollars(700), supplies.money_left());
+        assert_eq!(700, store.stock(SupplyItem::Food));
+        assert!(String::from_utf8(cout.into_inner()).unwrap().contains(SUPPLIES_BUY_CANCELLED));
     }
-}
\ No newline at end of file
+}
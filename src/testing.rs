@@ -0,0 +1,78 @@
+//! Setup shared by the tests of the game's modules
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::time::Duration;
+use rand::SeedableRng;
+use chrono::NaiveDate;
+use rand::rngs::StdRng;
+use crate::shooting::*;
+use crate::clock::*;
+use crate::prompt::*;
+use crate::money::*;
+use crate::store::*;
+use crate::supplies::*;
+use crate::trip::*;
+
+/// A shot taken in a test, the RNG is seeded so the word asked for is known in advance
+pub struct ShotFixture {
+    pub rng: StdRng,
+    pub word: &'static str,
+    pub clock: ScriptedClock,
+    pub prompter: ScriptedPrompter,
+    pub cout: Cursor<Vec<u8>>,
+}
+
+impl ShotFixture {
+    /// Set up a shot, typing `typed` (or the word asked for when it is None) after `reaction`
+    pub fn new(typed: Option<&str>, reaction: Duration) -> ShotFixture {
+        let rng = StdRng::seed_from_u64(1847);
+        let word = shot_word(&mut rng.clone());
+        let prompter = ScriptedPrompter::new(&[typed.unwrap_or(word)]);
+        let clock = ScriptedClock::reactions(&[reaction]);
+        ShotFixture { rng, word, clock, prompter, cout: Cursor::new(Vec::new()) }
+    }
+
+    /// Read back everything written out around the shot
+    pub fn output(&mut self) -> String {
+        read_out(&mut self.cout)
+    }
+}
+
+/// Supplies for a test: a $250 oxen team plus the given food, bullets, clothing and misc
+pub fn outfitted(food: u32, bullets: u32, clothes: u32, misc: u32) -> Supplies {
+    let mut supplies = Supplies::new();
+    let mut store = Store::independence();
+    supplies.buy_oxen(Money::from_dollars(250)).unwrap();
+    supplies.buy_food(&mut store, Money::from_dollars(food)).unwrap();
+    let belts = bullets.div_ceil(BULLETS_PER_DOLLAR);
+    supplies.buy_ammo(&mut store, Money::from_dollars(belts)).unwrap();
+    supplies.use_ammo(belts * BULLETS_PER_DOLLAR - bullets);
+    supplies.buy_clothes(&mut store, Money::from_dollars(clothes)).unwrap();
+    supplies.buy_misc(&mut store, Money::from_dollars(misc)).unwrap();
+    supplies
+}
+
+/// A date on the trail in 1847
+pub fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(1847, month, day).unwrap()
+}
+
+/// A trip that has taken `turns` turns (at a steady pace) to travel `miles`
+pub fn trip_after(turns: u32, miles: u32) -> Trip {
+    let mut trip = Trip::new();
+    let mut rng = StdRng::seed_from_u64(1847);
+    for _ in 0..turns {
+        trip.turn(OxenCondition::new(250), &mut rng);
+    }
+    let traveled = trip.miles_traveled();
+    trip.reverse(traveled);
+    trip.travel(miles);
+    trip
+}
+
+/// Read back everything a test wrote out
+pub fn read_out(cout: &mut Cursor<Vec<u8>>) -> String {
+    cout.seek(SeekFrom::Start(0)).unwrap();
+    let mut out = String::new();
+    cout.read_to_string(&mut out).unwrap();
+    out
+}
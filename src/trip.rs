@@ -1,11 +1,93 @@
 use rand::Rng;
-use rand::rngs::ThreadRng;
-use chrono::{NaiveDate, Duration};
+use chrono::{NaiveDate, Duration, Datelike};
+use crate::save::*;
+
+/// The length of the Oregon Trail, from Independence to Oregon City
+pub const OREGON_MILES: u32 = 2040;
+/// Each turn of the game covers 2 weeks of travel
+pub const TURN_DAYS: i64 = 14;
+
+/// Warn the party once only this many turns remain before the deadline
+pub const DEADLINE_WARNING_TURNS: i64 = 3;
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    /// Retrieve the season for a date
+    pub fn from_date(date: NaiveDate) -> Season {
+        match date.month() {
+            3..=5 => Season::Spring,
+            6..=8 => Season::Summer,
+            9..=11 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+}
+
+/// The condition of the oxen team (A in the BASIC code), which sets the pace of travel
+///     It starts out as what was spent on the team, and drops as the oxen are injured, stolen or run ragged
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct OxenCondition(u32);
+
+impl OxenCondition {
+    /// Constructor
+    pub fn new(condition: u32) -> OxenCondition {
+        OxenCondition(condition)
+    }
+
+    /// Retrieve the condition of the team, 0 is a team that can barely pull the wagon
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+
+    /// Improve the team (e.g. buying more oxen)
+    pub fn strengthen(&mut self, amount: u32) {
+        self.0 = self.0.saturating_add(amount);
+    }
+
+    /// Worsen the team, which can never drop below 0
+    pub fn injure(&mut self, amount: u32) {
+        self.0 = self.0.saturating_sub(amount);
+    }
+
+    /// Retrieve how many miles a turn the team gains (or loses) compared to a $220 team, calculated as (BASIC code):
+    ///     (A-220)/5
+    pub fn pace(&self) -> f64 {
+        (self.0 as f64 - 220.0) / 5.0
+    }
+}
+
+/// Retrieve the miles traveled in a turn given the oxen and a random roll (0.0 - 1.0), calculated as (BASIC code):
+///     200+(A-220)/5+10*RND()
+///     Based on this calculation, the amount spent on oxen has the biggest impact:
+///         If Oxen = 200, Mileage: 196 <==> 206
+///         If Oxen = 300, Mileage: 216 <==> 226
+///     Even a team in the worst condition still makes some progress, never going backwards
+pub fn turn_miles(oxen: OxenCondition, roll: f64) -> u32 {
+    (200.0 + oxen.pace() + 10.0 * roll).max(0.0) as u32
+}
 
 pub struct Trip {
     miles_traveled: u32,
     current_date: NaiveDate,
-    rng: ThreadRng,
+    start_date: NaiveDate,
+    turn_start_miles: u32,
+}
+
+impl Default for Trip {
+    fn default() -> Trip {
+        Trip::new()
+    }
 }
 
 impl Trip {
@@ -13,13 +95,33 @@ impl Trip {
     ///     Miles Traveled will be initialized to 0
     ///     Current Date will be initialized to March 29, 1847
     pub fn new() -> Trip {
+        let start_date = NaiveDate::from_ymd_opt(1847, 3, 29).unwrap();
         Trip {
             miles_traveled: 0,
-            current_date: NaiveDate::from_ymd(1847, 03, 29),
-            rng: rand::thread_rng(),
+            current_date: start_date,
+            start_date,
+            turn_start_miles: 0,
         }
     }
 
+    /// Save how far along the trail (and the calendar) the trip is
+    pub fn save(&self, file: &mut SaveFile) {
+        file.set("trip.miles", self.miles_traveled);
+        file.set("trip.date", self.current_date);
+        file.set("trip.start_date", self.start_date);
+        file.set("trip.turn_start_miles", self.turn_start_miles);
+    }
+
+    /// Load the trip, as saved
+    pub fn load(file: &SaveFile) -> Result<Trip, SaveError> {
+        Ok(Trip {
+            miles_traveled: file.get("trip.miles")?,
+            current_date: file.get("trip.date")?,
+            start_date: file.get("trip.start_date")?,
+            turn_start_miles: file.get("trip.turn_start_miles")?,
+        })
+    }
+
     /// Retrieve the # of miles traveled so far
     pub fn miles_traveled(&mut self) -> u32 {
         self.miles_traveled
@@ -30,39 +132,95 @@ impl Trip {
         self.current_date
     }
 
+    /// Retrieve the season of the current date of travel
+    pub fn season(&self) -> Season {
+        Season::from_date(self.current_date)
+    }
+
+    /// Retrieve the last date a turn can start before winter snows close the mountain passes
+    ///     December 20, 1847 is the start of the 20th turn (40 weeks after setting out)
+    pub fn deadline(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.start_date.year(), 12, 20).unwrap()
+    }
+
+    /// Retrieve the number of turns left (including the current turn) before the deadline
+    pub fn turns_left(&self) -> i64 {
+        if self.current_date > self.deadline() {
+            return 0;
+        }
+        (self.deadline() - self.current_date).num_days() / TURN_DAYS + 1
+    }
+
+    /// Has the party been on the trail too long, with the passes closed for the winter?
+    pub fn out_of_time(&self) -> bool {
+        self.turns_left() == 0
+    }
+
     /// Travel the Oregon Trail by a specific number of miles
     pub fn travel(&mut self, miles: u32) {
-        self.miles_traveled += miles;
+        self.miles_traveled = self.miles_traveled.saturating_add(miles);
     }
 
     /// Completes a portion of the Oregon Trail Trip, a "turn" of the game
-    ///     This includes traveling X miles and the passing of 2 weeks
-    ///     Where X miles equals a distance calculated as (BASIC code):
-    ///         200+((oxen)-220)/5+10*RND()
-    ///     Based on this calculation, the amount spent on oxen has the biggest impact:
-    ///         If Oxen = 200, Mileage: 197 <==> 199
-    ///         If Oxen = 300, Mileage: 213 <==> 206
-    pub fn turn(&mut self, oxen: u32) {
-        let turn_miles = 200 + ((oxen - 220) / (5 + self.rng.gen_range(0..10)));
-        self.miles_traveled += turn_miles;
-        self.current_date += Duration::days(14);
+    ///     This includes traveling the miles the oxen can manage (see turn_miles) and the passing of 2 weeks
+    pub fn turn<G: Rng>(&mut self, oxen: OxenCondition, rng: &mut G) {
+        self.turn_start_miles = self.miles_traveled;
+        let roll = rng.gen::<f64>();
+        self.miles_traveled = self.miles_traveled.saturating_add(turn_miles(oxen, roll));
+        self.current_date += Duration::days(TURN_DAYS);
+    }
+
+    /// Has the trip made it all the way to Oregon City?
+    pub fn arrived(&self) -> bool {
+        self.miles_traveled >= OREGON_MILES
+    }
+
+    /// Retrieve the fraction (0.0 - 1.0) of the last turn it took to reach Oregon City, calculated as (BASIC code):
+    ///     (2040-M2)/(M-M2), where M2 is the miles traveled at the start of the turn
+    pub fn final_turn_fraction(&self) -> f64 {
+        if self.miles_traveled <= self.turn_start_miles {
+            return 1.0;
+        }
+        let fraction = OREGON_MILES.saturating_sub(self.turn_start_miles) as f64
+            / (self.miles_traveled - self.turn_start_miles) as f64;
+        fraction.clamp(0.0, 1.0)
+    }
+
+    /// Retrieve the exact date the trip reached Oregon City, part way through the last turn
+    pub fn arrival_date(&self) -> NaiveDate {
+        let days_used = (self.final_turn_fraction() * TURN_DAYS as f64) as i64;
+        self.current_date - Duration::days(TURN_DAYS - days_used)
+    }
+
+    /// Retrieve the total number of days spent on the trail, through the arrival in Oregon City
+    pub fn trip_days(&self) -> i64 {
+        (self.arrival_date() - self.start_date).num_days()
+    }
+
+    /// Retrieve the portion of a turn's supplies (e.g. food eaten) not needed after reaching Oregon City
+    pub fn final_turn_unused(&self, amount: u32) -> u32 {
+        ((1.0 - self.final_turn_fraction()) * amount as f64) as u32
     }
 
     /// Something bad happened, we need to go backwards or delay the trip by a specific number of miles
+    ///     The trip can never go back past Independence (0 miles)
     pub fn reverse(&mut self, miles: u32) {
-        self.miles_traveled -= miles;
+        self.miles_traveled = self.miles_traveled.saturating_sub(miles);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use proptest::prelude::*;
 
     #[test]
     fn test_trip_constructor() {
         let mut trip = Trip::new();
         assert_eq!(0, trip.miles_traveled());
-        assert_eq!(NaiveDate::from_ymd(1847, 03, 29), trip.current_date());
+        assert_eq!(NaiveDate::from_ymd_opt(1847, 3, 29).unwrap(), trip.current_date());
     }
 
     #[test]
@@ -80,11 +238,166 @@ mod tests {
         assert_eq!(100, trip.miles_traveled());
     }
 
+    #[test]
+    fn test_trip_reverse_past_start() {
+        let mut trip = Trip::new();
+        trip.travel(20);
+        trip.reverse(45);
+        assert_eq!(0, trip.miles_traveled());
+    }
+
+    #[test]
+    fn test_trip_arrived() {
+        let mut trip = Trip::new();
+        trip.travel(OREGON_MILES - 1);
+        assert!(!trip.arrived());
+        trip.travel(1);
+        assert!(trip.arrived());
+    }
+
+    #[test]
+    fn test_trip_final_turn() {
+        let mut trip = Trip::new();
+        let mut rng = StdRng::seed_from_u64(1847);
+        trip.travel(1900);
+        trip.turn(OxenCondition::new(300), &mut rng);
+        // Force a 280 mile final turn, half of which was needed to reach Oregon City
+        trip.miles_traveled = 1900 + 280;
+        assert!((trip.final_turn_fraction() - 0.5).abs() < 1e-9);
+        assert_eq!(NaiveDate::from_ymd_opt(1847, 4, 5).unwrap(), trip.arrival_date());
+        assert_eq!(7, trip.trip_days());
+        assert_eq!(7, trip.final_turn_unused(14));
+    }
+
+    #[test]
+    fn test_trip_arrival_date() {
+        let mut trip = Trip::new();
+        let mut rng = StdRng::seed_from_u64(1847);
+        for _ in 0..12 {
+            trip.turn(OxenCondition::new(250), &mut rng);
+        }
+        trip.miles_traveled = 1900;
+        trip.turn(OxenCondition::new(250), &mut rng);
+        trip.miles_traveled = 2180;
+        assert_eq!(NaiveDate::from_ymd_opt(1847, 9, 20).unwrap(), trip.arrival_date());
+        assert_eq!(175, trip.trip_days());
+    }
+
+    #[test]
+    fn test_trip_final_turn_no_progress() {
+        let trip = Trip::new();
+        assert!((trip.final_turn_fraction() - 1.0).abs() < 1e-9);
+        assert_eq!(trip.start_date, trip.arrival_date());
+        assert_eq!(0, trip.final_turn_unused(18));
+    }
+
+    #[test]
+    fn test_season_from_date() {
+        assert_eq!(Season::Spring, Season::from_date(NaiveDate::from_ymd_opt(1847, 3, 29).unwrap()));
+        assert_eq!(Season::Summer, Season::from_date(NaiveDate::from_ymd_opt(1847, 7, 5).unwrap()));
+        assert_eq!(Season::Autumn, Season::from_date(NaiveDate::from_ymd_opt(1847, 11, 22).unwrap()));
+        assert_eq!(Season::Winter, Season::from_date(NaiveDate::from_ymd_opt(1847, 12, 20).unwrap()));
+        assert_eq!(Season::Winter, Season::from_date(NaiveDate::from_ymd_opt(1848, 1, 3).unwrap()));
+    }
+
+    #[test]
+    fn test_trip_season() {
+        let trip = Trip::new();
+        assert_eq!(Season::Spring, trip.season());
+    }
+
+    #[test]
+    fn test_trip_deadline() {
+        let mut trip = Trip::new();
+        let mut rng = StdRng::seed_from_u64(1847);
+        assert_eq!(NaiveDate::from_ymd_opt(1847, 12, 20).unwrap(), trip.deadline());
+        assert_eq!(20, trip.turns_left());
+        for _ in 0..19 {
+            trip.turn(OxenCondition::new(250), &mut rng);
+            assert!(!trip.out_of_time());
+        }
+        assert_eq!(NaiveDate::from_ymd_opt(1847, 12, 20).unwrap(), trip.current_date());
+        assert_eq!(1, trip.turns_left());
+        trip.turn(OxenCondition::new(250), &mut rng);
+        assert_eq!(0, trip.turns_left());
+        assert!(trip.out_of_time());
+    }
+
     #[test]
     fn test_trip_turn() {
         let mut trip = Trip::new();
-        trip.turn(300);
-        assert!(trip.miles_traveled() > 200);
-        assert!(trip.miles_traveled() < 300);
+        let mut rng = StdRng::seed_from_u64(1847);
+        let expected = turn_miles(OxenCondition::new(300), rng.clone().gen::<f64>());
+        trip.turn(OxenCondition::new(300), &mut rng);
+        assert_eq!(expected, trip.miles_traveled());
+        assert!((216..=226).contains(&expected));
+        assert_eq!(NaiveDate::from_ymd_opt(1847, 4, 12).unwrap(), trip.current_date());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_trip_turn_reproducible() {
+        let mut first = Trip::new();
+        let mut second = Trip::new();
+        let mut first_rng = StdRng::seed_from_u64(1847);
+        let mut second_rng = StdRng::seed_from_u64(1847);
+        for _ in 0..10 {
+            first.turn(OxenCondition::new(250), &mut first_rng);
+            second.turn(OxenCondition::new(250), &mut second_rng);
+            assert_eq!(first.miles_traveled(), second.miles_traveled());
+        }
+    }
+
+    #[test]
+    fn test_oxen_condition() {
+        let mut oxen = OxenCondition::new(220);
+        assert!(oxen.pace().abs() < 1e-9);
+        oxen.injure(20);
+        assert_eq!(200, oxen.value());
+        assert!((oxen.pace() + 4.0).abs() < 1e-9);
+        oxen.injure(500);
+        assert_eq!(0, oxen.value());
+        oxen.strengthen(300);
+        assert_eq!(300, oxen.value());
+    }
+
+    #[test]
+    fn test_turn_miles_worst_oxen() {
+        assert_eq!(156, turn_miles(OxenCondition::new(0), 0.0));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_turn_miles_matches_basic(oxen in 200u32..=300, roll in 0.0f64..1.0) {
+            let miles = turn_miles(OxenCondition::new(oxen), roll);
+            let expected = 200.0 + (oxen as f64 - 220.0) / 5.0 + 10.0 * roll;
+            prop_assert_eq!(expected.floor() as u32, miles);
+            prop_assert!((196..=226).contains(&miles));
+        }
+
+        #[test]
+        fn prop_better_oxen_go_further(oxen in 200u32..300, roll in 0.0f64..1.0) {
+            prop_assert!(turn_miles(OxenCondition::new(oxen), roll) <= turn_miles(OxenCondition::new(oxen + 1), roll));
+        }
+
+        #[test]
+        fn prop_injured_oxen_still_travel(oxen in 200u32..=300, injury in 0u32..=1000) {
+            let mut condition = OxenCondition::new(oxen);
+            condition.injure(injury);
+            let mut trip = Trip::new();
+            let mut rng = StdRng::seed_from_u64(1847);
+            trip.turn(condition, &mut rng);
+            prop_assert!(trip.miles_traveled() >= 156);
+            prop_assert!(trip.miles_traveled() <= turn_miles(OxenCondition::new(oxen), 1.0));
+        }
+
+        #[test]
+        fn prop_reverse_never_passes_start(oxen in 200u32..=300, miles in 0u32..=5000) {
+            let mut trip = Trip::new();
+            let mut rng = StdRng::seed_from_u64(1847);
+            trip.turn(OxenCondition::new(oxen), &mut rng);
+            let traveled = trip.miles_traveled();
+            trip.reverse(miles);
+            prop_assert_eq!(traveled.saturating_sub(miles), trip.miles_traveled());
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::io::Write;
+use rand::Rng;
+use chrono::{NaiveDate, Datelike};
+use crate::eating::*;
+use crate::finish::*;
+use crate::illness::*;
+use crate::mountains::*;
+use crate::supplies::*;
+use crate::trip::*;
+#[cfg(test)]
+use std::io::Cursor;
+#[cfg(test)]
+use crate::testing::*;
+#[cfg(test)]
+use rand::SeedableRng;
+#[cfg(test)]
+use rand::rngs::StdRng;
+
+/// Late autumn starts on October 15, after which the weather turns cold
+pub const LATE_AUTUMN: (u32, u32) = (10, 15);
+/// Clothing needed to keep warm in the cold out on the plains
+pub const CLOTHES_REQUIRED_PLAINS: u32 = 22;
+/// Clothing needed to keep warm in the cold up in the mountains
+pub const CLOTHES_REQUIRED_MOUNTAINS: u32 = 30;
+/// Clothing worn out by each turn spent in the cold
+pub const CLOTHES_WEAR: u32 = 5;
+
+const WEATHER_WORN: &str = "Your clothing is wearing thin---you'll need more to keep warm\n";
+const WEATHER_COLD: &str = "It's getting cold---you don't have enough clothing to keep you warm\n";
+const WEATHER_FREEZING: &str = "Freezing cold---your clothing is nowhere near enough to keep you warm\n";
+
+/// Retrieve the clothing required to keep the party warm, none until late autumn
+///     Once the weather turns cold the mountains (past 950 miles) require more clothing than the plains
+pub fn clothing_required(date: NaiveDate, miles: u32) -> u32 {
+    let late_autumn = NaiveDate::from_ymd_opt(date.year(), LATE_AUTUMN.0, LATE_AUTUMN.1).unwrap();
+    if date < late_autumn && Season::from_date(date) != Season::Winter {
+        return 0;
+    }
+    if miles > MOUNTAINS_MILES { CLOTHES_REQUIRED_MOUNTAINS } else { CLOTHES_REQUIRED_PLAINS }
+}
+
+/// Face the cold weather, wearing out clothing each turn once it turns cold
+///     Nothing is said while the clothing keeps the party warm, unless it wears out to less than is required
+///     Without the required clothing the party falls ill,
+///     and with less than half of it the party freezes to death
+pub fn cold_weather<W: Write, G: Rng>(supplies: &mut Supplies, trip: &mut Trip, health: &mut Health,
+        eating: &EatingQuality, out: &mut W, rng: &mut G) -> Result<(), DeathCause> {
+    let required = clothing_required(trip.current_date(), trip.miles_traveled());
+    if required == 0 {
+        return Ok(());
+    }
+
+    let clothes = supplies.clothes_left();
+    supplies.lose_clothes(CLOTHES_WEAR);
+    if clothes >= required {
+        if supplies.clothes_left() < required {
+            out.write_all(WEATHER_WORN.as_bytes()).unwrap();
+        }
+        Ok(())
+    } else if clothes >= required / 2 {
+        out.write_all(WEATHER_COLD.as_bytes()).unwrap();
+        health.fall_ill(eating, supplies, trip, out, rng)
+    } else {
+        out.write_all(WEATHER_FREEZING.as_bytes()).unwrap();
+        Err(DeathCause::Exposure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_cold_weather(clothes: u32, turns: u32, miles: u32) -> (Supplies, Result<(), DeathCause>, String) {
+        let mut supplies = outfitted(0, 0, clothes, 50);
+        let mut trip = trip_after(turns, miles);
+        let mut health = Health::new();
+        let mut cout = Cursor::new(Vec::new());
+        let mut rng = StdRng::seed_from_u64(1847);
+
+        let result = cold_weather(&mut supplies, &mut trip, &mut health, &EatingQuality::Well, &mut cout, &mut rng);
+        (supplies, result, read_out(&mut cout))
+    }
+
+    #[test]
+    fn test_clothing_required() {
+        assert_eq!(0, clothing_required(date(3, 29), 0));
+        assert_eq!(0, clothing_required(date(10, 14), 1200));
+        assert_eq!(CLOTHES_REQUIRED_PLAINS, clothing_required(date(10, 15), 900));
+        assert_eq!(CLOTHES_REQUIRED_MOUNTAINS, clothing_required(date(10, 15), 1200));
+        assert_eq!(CLOTHES_REQUIRED_MOUNTAINS, clothing_required(date(12, 20), 2000));
+    }
+
+    #[test]
+    fn test_cold_weather_not_yet() {
+        let (mut supplies, result, weather_out) = run_cold_weather(10, 0, 1000);
+        assert_eq!(Ok(()), result);
+        assert_eq!("", weather_out);
+        assert_eq!(10, supplies.clothes_left());
+    }
+
+    #[test]
+    fn test_cold_weather_warm() {
+        // 15 turns takes the trip to November 8
+        let (mut supplies, result, weather_out) = run_cold_weather(40, 15, 1000);
+        assert_eq!(Ok(()), result);
+        assert_eq!("", weather_out);
+        assert_eq!(40 - CLOTHES_WEAR, supplies.clothes_left());
+    }
+
+    #[test]
+    fn test_cold_weather_wearing_thin() {
+        let (mut supplies, result, weather_out) = run_cold_weather(CLOTHES_REQUIRED_MOUNTAINS, 15, 1000);
+        assert_eq!(Ok(()), result);
+        assert_eq!(WEATHER_WORN, weather_out);
+        assert_eq!(CLOTHES_REQUIRED_MOUNTAINS - CLOTHES_WEAR, supplies.clothes_left());
+    }
+
+    #[test]
+    fn test_cold_weather_cold() {
+        let (mut supplies, result, weather_out) = run_cold_weather(20, 15, 1000);
+        assert_eq!(Ok(()), result);
+        assert!(weather_out.starts_with(WEATHER_COLD));
+        assert!(supplies.misc_left() < 50);
+    }
+
+    #[test]
+    fn test_cold_weather_freezing() {
+        let (_, result, weather_out) = run_cold_weather(10, 15, 1000);
+        assert_eq!(Err(DeathCause::Exposure), result);
+        assert_eq!(WEATHER_FREEZING, weather_out);
+    }
+}